@@ -0,0 +1,132 @@
+use std::{env, path::PathBuf};
+
+use crate::error::{Error, Result};
+use crate::game::bot::Difficulty;
+use crate::game::royale::MAX_BOTS;
+use crate::player::Player;
+
+/// Command line options.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub cpu: Option<Difficulty>,
+    /// Bots to play a battle royale against.
+    pub royale: Option<usize>,
+    pub bot: Option<String>,
+    pub profile: Option<String>,
+    pub export_profile: Option<PathBuf>,
+    pub player: Option<String>,
+    pub tutorial: bool,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(env::args().skip(1))
+    }
+
+    fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--cpu" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::Usage("--cpu needs a difficulty".to_string()))?;
+                    parsed.cpu = Some(value.parse().map_err(Error::Usage)?);
+                }
+                "--royale" => {
+                    let value = args.next().ok_or_else(|| {
+                        Error::Usage("--royale needs a number of bots".to_string())
+                    })?;
+                    let bots = value
+                        .parse()
+                        .ok()
+                        .filter(|bots| (1..=MAX_BOTS).contains(bots))
+                        .ok_or_else(|| {
+                            Error::Usage(format!(
+                                "--royale needs 1 to {} bots, got {:?}",
+                                MAX_BOTS, value
+                            ))
+                        })?;
+                    parsed.royale = Some(bots);
+                }
+                "--bot" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::Usage("--bot needs a command".to_string()))?;
+                    parsed.bot = Some(value);
+                }
+                "--profile" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::Usage("--profile needs a name".to_string()))?;
+                    parsed.profile = Some(value);
+                }
+                "--export-profile" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::Usage("--export-profile needs a file".to_string()))?;
+                    parsed.export_profile = Some(value.into());
+                }
+                "--player" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Error::Usage("--player needs a name".to_string()))?;
+                    Player::validate_name(&value).map_err(Error::Usage)?;
+                    parsed.player = Some(value);
+                }
+                "--tutorial" => parsed.tutorial = true,
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(Error::Usage(format!("unexpected argument {:?}", arg))),
+            }
+        }
+
+        if parsed.cpu.is_some() && parsed.royale.is_some() {
+            return Err(Error::Usage(
+                "--cpu and --royale cannot be used together".to_string(),
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse_from(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(&[]).unwrap().cpu, None);
+        assert_eq!(
+            parse(&["--cpu", "hard"]).unwrap().cpu,
+            Some(Difficulty::Hard)
+        );
+        assert!(parse(&["--help"]).unwrap().help);
+        assert!(parse(&["--tutorial"]).unwrap().tutorial);
+        assert_eq!(
+            parse(&["--profile", "lefty"]).unwrap().profile.as_deref(),
+            Some("lefty")
+        );
+        assert_eq!(
+            parse(&["--player", "ana"]).unwrap().player.as_deref(),
+            Some("ana")
+        );
+
+        assert_eq!(parse(&["--royale", "8"]).unwrap().royale, Some(8));
+
+        assert!(parse(&["--cpu"]).is_err());
+        assert!(parse(&["--royale", "0"]).is_err());
+        assert!(parse(&["--royale", "many"]).is_err());
+        assert!(parse(&["--royale", "2", "--cpu", "easy"]).is_err());
+        assert!(parse(&["--cpu", "impossible"]).is_err());
+        assert!(parse(&["--export-profile"]).is_err());
+        assert!(parse(&["--player", "../ana"]).is_err());
+        assert!(parse(&["--foo"]).is_err());
+    }
+}
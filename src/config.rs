@@ -0,0 +1,516 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+use crate::game::{
+    level::{Goal, MIN_FALL_INTERVAL},
+    piece_set::PieceSet,
+    randomizer::Randomizer,
+    royale::Targeting,
+    shape::SpawnRule,
+    tetris::BOARD_WIDTH,
+};
+use crate::input::Controls;
+use crate::player::Player;
+use crate::profile::{Profile, DEFAULT_PROFILE};
+use crate::ui::i18n::Locale;
+
+/// User settings, read from `config.toml` in the tetris_tui config directory.
+/// Any field missing from the file falls back to its default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Ticks to wait between a piece locking and the next one spawning (ARE).
+    pub spawn_delay: u32,
+    /// Initial board zoom, changed in game with `+`/`-`.
+    pub zoom: Zoom,
+    /// Draw each cell two columns wide so it looks square on a terminal
+    /// whose cells are about twice as tall as they are wide.
+    pub aspect_correct: bool,
+    /// Columns right of the board centre that pieces spawn at, or left if
+    /// negative.
+    pub spawn_column: i32,
+    /// Clockwise quarter turns (0-3) applied to a shape when it spawns.
+    pub spawn_orientation: HashMap<String, u8>,
+    /// How much faster than gravity a piece falls while soft dropping,
+    /// overriding the profile.
+    pub soft_drop: Option<SoftDrop>,
+    /// How many lines it takes to level up.
+    pub goal: Goal,
+    /// How pieces are dealt, `"random"` or a `"bag"` of the whole set.
+    pub randomizer: Randomizer,
+    /// Who the player's garbage goes to in a battle royale.
+    pub targeting: Targeting,
+    /// File of custom pieces to play with instead of the tetrominoes.
+    pub pieces: Option<PathBuf>,
+    /// The set read from `pieces`, or the tetrominoes.
+    #[serde(skip)]
+    pub piece_set: Arc<PieceSet>,
+    /// Hand out bonus objectives during the game.
+    pub objectives: bool,
+    /// Show each column's height and holes under the board, toggled in game
+    /// with F4.
+    pub analysis: bool,
+    /// Draw the boards flipped left to right, with the keys for moving left
+    /// and right swapped to match.
+    pub mirror: bool,
+    /// Language of the text in game.
+    pub language: Locale,
+    /// How the screen is redrawn, `"minimal"` for slow connections like SSH.
+    pub render: RenderMode,
+    /// Keys for actions on top of the profile, e.g. `rotate_180 = "a"`.
+    pub keys: BTreeMap<String, String>,
+    /// Controls profile to play with: a built-in one, one from `profiles` or
+    /// the path of an exported profile file.
+    pub profile: Option<String>,
+    /// User defined profiles, by name.
+    pub profiles: HashMap<String, Profile>,
+    /// Local player to play as, unless one is picked with `--player`.
+    pub player: Option<String>,
+    /// Show desktop notifications for new personal bests, in builds
+    /// with the `notifications` feature.
+    pub notifications: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            spawn_delay: 0,
+            zoom: Zoom::Large,
+            aspect_correct: true,
+            spawn_column: 0,
+            spawn_orientation: HashMap::new(),
+            soft_drop: None,
+            goal: Goal::Fixed,
+            randomizer: Randomizer::Random,
+            targeting: Targeting::Random,
+            pieces: None,
+            piece_set: Arc::default(),
+            objectives: false,
+            analysis: false,
+            mirror: false,
+            language: Locale::En,
+            render: RenderMode::Full,
+            keys: BTreeMap::new(),
+            profile: None,
+            profiles: HashMap::new(),
+            player: None,
+            notifications: false,
+        }
+    }
+}
+
+/// Soft drop speed, written as a gravity multiplier such as `20` or as
+/// `"instant"` to drop straight to the floor without locking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoftDrop {
+    Factor(u32),
+    Instant,
+}
+
+impl SoftDrop {
+    /// Time between rows while soft dropping under `gravity`, or `None` when
+    /// the piece should drop all the way at once.
+    pub fn interval(self, gravity: Duration) -> Option<Duration> {
+        match self {
+            SoftDrop::Factor(factor) => Some((gravity / factor.max(1)).max(MIN_FALL_INTERVAL)),
+            SoftDrop::Instant => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SoftDrop {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct SoftDropVisitor;
+
+        impl<'de> de::Visitor<'de> for SoftDropVisitor {
+            type Value = SoftDrop;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a gravity multiplier of at least 1 or \"instant\"")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<SoftDrop, E> {
+                match u32::try_from(value) {
+                    Ok(factor) if factor >= 1 => Ok(SoftDrop::Factor(factor)),
+                    _ => Err(E::invalid_value(de::Unexpected::Signed(value), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<SoftDrop, E> {
+                if value == "instant" {
+                    Ok(SoftDrop::Instant)
+                } else {
+                    Err(E::invalid_value(de::Unexpected::Str(value), &self))
+                }
+            }
+        }
+
+        deserializer.deserialize_any(SoftDropVisitor)
+    }
+}
+
+impl Serialize for SoftDrop {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            SoftDrop::Factor(factor) => serializer.serialize_u32(*factor),
+            SoftDrop::Instant => serializer.serialize_str("instant"),
+        }
+    }
+}
+
+/// How much is drawn and how often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderMode {
+    /// Redraw every loop iteration with a border around each board cell.
+    Full,
+    /// Redraw only after something changed, with plain cells, to send as
+    /// little as possible to the terminal.
+    Minimal,
+}
+
+/// Upper bound on the size of a board cell. The board still shrinks to fit
+/// the terminal when the chosen zoom is too big.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Zoom {
+    Small,
+    Medium,
+    Large,
+}
+
+impl Zoom {
+    /// Terminal rows used by one board cell.
+    pub fn cell_rows(self) -> u16 {
+        match self {
+            Zoom::Small => 1,
+            Zoom::Medium => 2,
+            Zoom::Large => 3,
+        }
+    }
+
+    pub fn zoom_in(self) -> Self {
+        match self {
+            Zoom::Small => Zoom::Medium,
+            _ => Zoom::Large,
+        }
+    }
+
+    pub fn zoom_out(self) -> Self {
+        match self {
+            Zoom::Large => Zoom::Medium,
+            _ => Zoom::Small,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, or the defaults if there is none.
+    pub fn load() -> Result<Self> {
+        match config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let mut config: Self = toml::from_str(content)?;
+        if let Some(path) = &config.pieces {
+            config.piece_set = Arc::new(PieceSet::read(path)?);
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (name, &turns) in &self.spawn_orientation {
+            if self.piece_set.find(name).is_none() {
+                return Err(Error::InvalidConfig(format!(
+                    "spawn_orientation: unknown piece shape {:?}",
+                    name
+                )));
+            }
+            if turns > 3 {
+                return Err(Error::InvalidConfig(format!(
+                    "spawn_orientation.{} must be between 0 and 3, got {}",
+                    name, turns
+                )));
+            }
+        }
+
+        for (name, profile) in &self.profiles {
+            profile.controls().map_err(|err| match err {
+                Error::InvalidConfig(reason) => {
+                    Error::InvalidConfig(format!("profiles.{}.{}", name, reason))
+                }
+                err => err,
+            })?;
+        }
+        self.controls()?;
+
+        if let Some(name) = &self.player {
+            Player::validate_name(name)
+                .map_err(|err| Error::InvalidConfig(format!("player: {}", err)))?;
+        }
+
+        let rule = self.spawn_rule();
+        for shape in self.piece_set.shapes() {
+            let piece = rule.spawn(&self.piece_set, shape, BOARD_WIDTH);
+            if piece
+                .iter_positions()
+                .any(|cell| cell.0 < 0 || cell.0 >= BOARD_WIDTH)
+            {
+                return Err(Error::InvalidConfig(format!(
+                    "spawn_column {} puts the {} piece outside the board",
+                    self.spawn_column,
+                    self.piece_set.name(shape)
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn spawn_rule(&self) -> SpawnRule {
+        let mut rule = SpawnRule {
+            column_offset: self.spawn_column,
+            ..SpawnRule::default()
+        };
+        for (name, &turns) in &self.spawn_orientation {
+            if let Some(shape) = self.piece_set.find(name) {
+                rule.turns[shape.index()] = turns;
+            }
+        }
+        rule
+    }
+
+    /// The selected profile with the top level `soft_drop` and `keys`
+    /// applied on top.
+    pub fn active_profile(&self) -> Result<Profile> {
+        let name = self.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+        let mut profile = match self.profiles.get(name) {
+            Some(profile) => profile.clone(),
+            None => match Profile::builtin(name) {
+                Some(profile) => profile,
+                None => Profile::read(Path::new(name))?,
+            },
+        };
+
+        profile.merge(&Profile {
+            soft_drop: self.soft_drop,
+            keys: self.keys.clone(),
+        });
+        Ok(profile)
+    }
+
+    pub fn controls(&self) -> Result<Controls> {
+        let mut controls = self.active_profile()?.controls()?;
+        if self.mirror {
+            controls.bindings = controls.bindings.mirrored();
+        }
+        Ok(controls)
+    }
+}
+
+/// How often the config file is checked for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Notices edits to the config file while the game runs by polling its
+/// modification time.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+    /// Profile picked on the command line, which wins over the file.
+    profile: Option<String>,
+}
+
+impl ConfigWatcher {
+    /// Watches the config file, or returns `None` if there is no config
+    /// directory to find it in.
+    pub fn new(profile: Option<String>) -> Option<Self> {
+        let path = config_path()?;
+        Some(Self {
+            modified: modified_time(&path),
+            path,
+            last_check: Instant::now(),
+            profile,
+        })
+    }
+
+    /// Rereads the config if the file changed since it was last read.
+    pub fn poll(&mut self) -> Option<Result<Config>> {
+        if self.last_check.elapsed() < WATCH_INTERVAL {
+            return None;
+        }
+        self.last_check = Instant::now();
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        Some(Config::load_from(&self.path).map(|mut config| {
+            if self.profile.is_some() {
+                config.profile = self.profile.clone();
+            }
+            config
+        }))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// `$TETRIS_TUI_CONFIG` if set, otherwise `config.toml` under
+/// `$XDG_CONFIG_HOME/tetris_tui` or `~/.config/tetris_tui`.
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("TETRIS_TUI_CONFIG") {
+        return Some(path.into());
+    }
+
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("tetris_tui").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::shape::Shape;
+
+    #[test]
+    fn test_parse() {
+        let config: Config = toml::from_str("spawn_delay = 2").unwrap();
+        assert_eq!(config.spawn_delay, 2);
+
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.spawn_delay, 0);
+        assert_eq!(config.zoom, Zoom::Large);
+        assert!(config.aspect_correct);
+
+        let config: Config = toml::from_str("zoom = \"small\"\naspect_correct = false").unwrap();
+        assert_eq!(config.zoom, Zoom::Small);
+        assert!(!config.aspect_correct);
+        assert_eq!(config.render, RenderMode::Full);
+        assert_eq!(config.goal, Goal::Fixed);
+        assert_eq!(config.randomizer, Randomizer::Random);
+        assert_eq!(config.targeting, Targeting::Random);
+
+        let config: Config = toml::from_str("render = \"minimal\"").unwrap();
+        assert_eq!(config.render, RenderMode::Minimal);
+
+        let config: Config = toml::from_str("targeting = \"leader\"").unwrap();
+        assert_eq!(config.targeting, Targeting::Leader);
+
+        let config: Config = toml::from_str("language = \"es\"").unwrap();
+        assert_eq!(config.language, Locale::Es);
+    }
+
+    #[test]
+    fn test_validate_spawn() {
+        let config = Config::parse("spawn_column = -1\n[spawn_orientation]\nI = 1").unwrap();
+        let rule = config.spawn_rule();
+        assert_eq!(rule.turns[Shape::I.index()], 1);
+        assert_eq!(rule.turns[Shape::T.index()], 0);
+        assert_eq!(rule.column_offset, -1);
+
+        assert!(Config::parse("[spawn_orientation]\nT = 4").is_err());
+        assert!(Config::parse("[spawn_orientation]\nX = 1").is_err());
+        assert!(Config::parse("spawn_column = 4").is_err());
+        assert!(Config::parse("spawn_column = -5").is_err());
+    }
+
+    #[test]
+    fn test_soft_drop() {
+        let soft_drop = Config::default().controls().unwrap().soft_drop;
+        assert_eq!(soft_drop, SoftDrop::Factor(20));
+
+        let soft_drop = Config::parse("soft_drop = 6")
+            .unwrap()
+            .controls()
+            .unwrap()
+            .soft_drop;
+        assert_eq!(soft_drop, SoftDrop::Factor(6));
+        assert_eq!(
+            soft_drop.interval(Duration::from_millis(600)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            soft_drop.interval(MIN_FALL_INTERVAL),
+            Some(MIN_FALL_INTERVAL)
+        );
+
+        let config = Config::parse("soft_drop = \"instant\"").unwrap();
+        assert_eq!(config.soft_drop, Some(SoftDrop::Instant));
+        assert_eq!(SoftDrop::Instant.interval(Duration::from_millis(600)), None);
+
+        assert!(Config::parse("soft_drop = 0").is_err());
+        assert!(Config::parse("soft_drop = \"fast\"").is_err());
+    }
+
+    #[test]
+    fn test_validate_keys() {
+        assert!(Config::parse("[keys]\nrotate_180 = \"z\"").is_ok());
+        assert!(Config::parse("[keys]\nrotate_180 = \"ctrl\"").is_err());
+        assert!(Config::parse("[keys]\nteleport = \"t\"").is_err());
+    }
+
+    #[test]
+    fn test_watcher() {
+        let path = env::temp_dir().join("tetris_tui_test_watch.toml");
+        fs::write(&path, "spawn_delay = 3").unwrap();
+        let mut watcher = ConfigWatcher {
+            path: path.clone(),
+            modified: None,
+            last_check: Instant::now() - WATCH_INTERVAL,
+            profile: Some("lefty".to_string()),
+        };
+
+        let config = watcher.poll().unwrap().unwrap();
+        assert_eq!(config.spawn_delay, 3);
+        assert_eq!(config.profile.as_deref(), Some("lefty"));
+
+        watcher.last_check = Instant::now() - WATCH_INTERVAL;
+        assert!(watcher.poll().is_none());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_profiles() {
+        let config = Config::parse("profile = \"classic\"\nsoft_drop = 4").unwrap();
+        let profile = config.active_profile().unwrap();
+        assert_eq!(profile.soft_drop, Some(SoftDrop::Factor(4)));
+        assert_eq!(profile.keys["rotate_180"], "none");
+
+        let config = Config::parse(
+            "profile = \"mine\"\n[keys]\nrotate = \"x\"\n[profiles.mine.keys]\nrotate = \"z\"\nquit = \"esc\"",
+        )
+        .unwrap();
+        let profile = config.active_profile().unwrap();
+        assert_eq!(profile.keys["rotate"], "x");
+        assert_eq!(profile.keys["quit"], "esc");
+
+        assert!(Config::parse("profile = \"missing\"").is_err());
+        assert!(Config::parse("[profiles.bad.keys]\nrotate = \"ctrl\"\n").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Logger(log::SetLoggerError),
+    Config(toml::de::Error),
+    InvalidConfig(String),
+    Usage(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "terminal I/O error: {}", err),
+            Error::Logger(err) => write!(f, "failed to initialize logger: {}", err),
+            Error::Config(err) => write!(f, "invalid config file: {}", err),
+            Error::InvalidConfig(reason) => write!(f, "invalid config: {}", reason),
+            Error::Usage(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Logger(_) => None,
+            Error::Config(err) => Some(err),
+            Error::InvalidConfig(_) | Error::Usage(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<log::SetLoggerError> for Error {
+    fn from(err: log::SetLoggerError) -> Self {
+        Error::Logger(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Config(err)
+    }
+}
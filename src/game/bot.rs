@@ -0,0 +1,379 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+    time::Duration,
+};
+
+use rand::Rng;
+
+use super::{
+    shape::{Cell, Piece},
+    tetris::{Direction, Tetris, TetrisBoard},
+    view::BoardView,
+};
+
+/// How well the CPU opponent plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// Whether the bot also places the next queued piece when judging where
+    /// to put the current one.
+    fn lookahead(self) -> bool {
+        self == Difficulty::Hard
+    }
+
+    /// Pause before each move the bot makes.
+    pub fn reaction_delay(self) -> Duration {
+        match self {
+            Difficulty::Easy => Duration::from_millis(350),
+            Difficulty::Normal => Duration::from_millis(180),
+            Difficulty::Hard => Duration::from_millis(60),
+        }
+    }
+
+    /// Chance of dropping a piece in a random spot instead of the best one.
+    fn misdrop_chance(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.2,
+            Difficulty::Normal => 0.05,
+            Difficulty::Hard => 0.0,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "unknown difficulty {:?}, expected easy, normal or hard",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Rotate,
+    Shift(Direction),
+}
+
+/// Plays a board by picking a placement for each new piece and then feeding
+/// the rotations and shifts needed to get there, one per call.
+pub struct Bot {
+    difficulty: Difficulty,
+    moves: VecDeque<Move>,
+    /// Row the top of the piece has to fall to before the moves are made,
+    /// for pieces that cannot rotate right where they spawn.
+    start_row: i32,
+    /// Piece count of the board when the current plan was made.
+    planned_for: Option<u32>,
+}
+
+impl Bot {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            moves: VecDeque::new(),
+            start_row: 0,
+            planned_for: None,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// The next move to make on `board`, planning a fresh placement whenever
+    /// a new piece has spawned.
+    pub fn next_move(&mut self, board: &TetrisBoard) -> Option<Move> {
+        let piece = board.current_piece()?;
+        let view = board.view();
+
+        if self.planned_for != Some(view.stats.pieces) {
+            self.planned_for = Some(view.stats.pieces);
+            self.plan(board, piece, &view);
+        }
+
+        if top_row(piece) < self.start_row {
+            return None;
+        }
+        self.moves.pop_front()
+    }
+
+    fn plan(&mut self, board: &TetrisBoard, piece: &Piece, view: &BoardView) {
+        let field = Field::from_view(view);
+        let placements = field.placements(piece);
+        let mut rng = rand::thread_rng();
+
+        let chosen = if placements.is_empty() {
+            self.moves.clear();
+            return;
+        } else if rng.gen_bool(self.difficulty.misdrop_chance()) {
+            &placements[rng.gen_range(0..placements.len())]
+        } else {
+            let next_piece = view
+                .queue
+                .first()
+                .filter(|_| self.difficulty.lookahead())
+                .map(|&shape| board.spawn(shape));
+
+            let score = |placement: &Placement| {
+                let (after, lines) = field.place(&placement.cells);
+                let score = match &next_piece {
+                    Some(next) => after.best_score(next).unwrap_or(f64::MIN),
+                    None => after.evaluate(),
+                };
+                score + LINES_WEIGHT * lines as f64
+            };
+
+            placements
+                .iter()
+                .map(|placement| (placement, score(placement)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(placement, _)| placement)
+                .expect("placements is not empty")
+        };
+
+        let direction = if chosen.shift < 0 {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        self.start_row = top_row(piece) + chosen.wait;
+        self.moves = (0..chosen.turns)
+            .map(|_| Move::Rotate)
+            .chain((0..chosen.shift.abs()).map(|_| Move::Shift(direction)))
+            .collect();
+    }
+}
+
+fn top_row(piece: &Piece) -> i32 {
+    piece.iter_positions().map(|cell| cell.1).min().unwrap_or(0)
+}
+
+/// Rows a piece may fall before the bot starts moving it, giving pieces that
+/// spawn against the top of the board room to rotate.
+const MAX_WAIT: i32 = 2;
+
+const HEIGHT_WEIGHT: f64 = -0.51;
+const LINES_WEIGHT: f64 = 0.76;
+const HOLES_WEIGHT: f64 = -0.36;
+const BUMPINESS_WEIGHT: f64 = -0.18;
+
+/// Where a piece can end up after falling `wait` rows, rotating `turns`
+/// times, shifting `shift` columns and falling the rest of the way.
+struct Placement {
+    wait: i32,
+    turns: u8,
+    shift: i32,
+    cells: Vec<Cell>,
+}
+
+/// The locked cells of a board, cheap to copy and modify while searching.
+#[derive(Clone)]
+struct Field {
+    width: i32,
+    height: i32,
+    filled: Vec<bool>,
+}
+
+impl Field {
+    fn from_view(view: &BoardView) -> Self {
+        Self {
+            width: view.width,
+            height: view.height,
+            filled: view.grid.iter().map(Option::is_some).collect(),
+        }
+    }
+
+    fn is_filled(&self, Cell(x, y): Cell) -> bool {
+        self.filled[(y * self.width + x) as usize]
+    }
+
+    fn fits(&self, piece: &Piece) -> bool {
+        piece.iter_positions().all(|cell| {
+            0 <= cell.0
+                && cell.0 < self.width
+                && 0 <= cell.1
+                && cell.1 < self.height
+                && !self.is_filled(cell)
+        })
+    }
+
+    /// Every spot `piece` can be moved to by letting it fall a little, then
+    /// rotating in place, shifting sideways and dropping. A spot reachable in
+    /// several ways is only listed once, with the shortest wait.
+    fn placements(&self, piece: &Piece) -> Vec<Placement> {
+        let mut placements = vec![];
+        let mut seen = HashSet::new();
+
+        for wait in 0..=MAX_WAIT {
+            let mut rotated = piece + Cell(0, wait);
+            if !self.fits(&rotated) {
+                break;
+            }
+
+            for turns in 0..4 {
+                if turns > 0 {
+                    rotated = rotated.rotate();
+                }
+                if !self.fits(&rotated) {
+                    break;
+                }
+
+                for step in [-1, 1] {
+                    let mut shift = if step < 0 { 0 } else { 1 };
+                    loop {
+                        let mut moved = &rotated + Cell(shift, 0);
+                        if !self.fits(&moved) {
+                            break;
+                        }
+                        while self.fits(&(&moved + Cell(0, 1))) {
+                            moved = &moved + Cell(0, 1);
+                        }
+
+                        let mut cells: Vec<Cell> = moved.iter_positions().collect();
+                        cells.sort_by_key(|cell| (cell.1, cell.0));
+                        if seen.insert(cells.clone()) {
+                            placements.push(Placement {
+                                wait,
+                                turns,
+                                shift,
+                                cells,
+                            });
+                        }
+                        shift += step;
+                    }
+                }
+            }
+        }
+
+        placements
+    }
+
+    /// Locks `cells` into a copy of the field and clears full lines,
+    /// returning the new field and the number of lines cleared.
+    fn place(&self, cells: &[Cell]) -> (Field, u32) {
+        let mut field = self.clone();
+        for &Cell(x, y) in cells {
+            field.filled[(y * self.width + x) as usize] = true;
+        }
+
+        let width = self.width as usize;
+        let rows: Vec<&[bool]> = field
+            .filled
+            .chunks(width)
+            .filter(|row| !row.iter().all(|&filled| filled))
+            .collect();
+        let lines = self.height as u32 - rows.len() as u32;
+
+        let mut filled = vec![false; lines as usize * width];
+        filled.extend(rows.concat());
+        field.filled = filled;
+
+        (field, lines)
+    }
+
+    fn best_score(&self, piece: &Piece) -> Option<f64> {
+        self.placements(piece)
+            .iter()
+            .map(|placement| {
+                let (after, lines) = self.place(&placement.cells);
+                after.evaluate() + LINES_WEIGHT * lines as f64
+            })
+            .max_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Scores how good the stack looks, higher is better.
+    fn evaluate(&self) -> f64 {
+        let mut heights = vec![0; self.width as usize];
+        let mut holes = 0;
+
+        for x in 0..self.width {
+            let top = (0..self.height).find(|&y| self.is_filled(Cell(x, y)));
+            if let Some(top) = top {
+                heights[x as usize] = self.height - top;
+                holes += (top..self.height)
+                    .filter(|&y| !self.is_filled(Cell(x, y)))
+                    .count();
+            }
+        }
+
+        let aggregate_height: i32 = heights.iter().sum();
+        let bumpiness: i32 = heights.windows(2).map(|w| (w[0] - w[1]).abs()).sum();
+
+        HEIGHT_WEIGHT * aggregate_height as f64
+            + HOLES_WEIGHT * holes as f64
+            + BUMPINESS_WEIGHT * bumpiness as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::shape::Shape;
+
+    #[test]
+    fn test_place_clears_lines() {
+        let field = Field {
+            width: 4,
+            height: 3,
+            filled: [
+                [false, false, false, false],
+                [true, true, false, true],
+                [true, true, false, true],
+            ]
+            .concat(),
+        };
+
+        let (after, lines) = field.place(&[Cell(2, 1), Cell(2, 2)]);
+
+        assert_eq!(lines, 2);
+        assert!(after.filled.iter().all(|&filled| !filled));
+    }
+
+    #[test]
+    fn test_placements_stay_on_board() {
+        let board = TetrisBoard::new_default();
+        let field = Field::from_view(&board.view());
+        let piece = board.spawn(Shape::T);
+
+        let placements = field.placements(&piece);
+
+        assert!(!placements.is_empty());
+        for placement in placements {
+            assert!(placement.cells.iter().any(|cell| cell.1 == 19));
+        }
+    }
+
+    #[test]
+    fn test_bot_survives() {
+        let mut board = TetrisBoard::new_default();
+        let mut bot = Bot::new(Difficulty::Hard);
+
+        for _ in 0..400 {
+            while let Some(action) = bot.next_move(&board) {
+                match action {
+                    Move::Rotate => board.rotate(),
+                    Move::Shift(direction) => board.shift(direction),
+                }
+            }
+            board.tick();
+        }
+
+        assert!(board.alive());
+    }
+}
@@ -0,0 +1,19 @@
+use super::shape::Shape;
+
+/// Something that happened on a board, collected as it plays so other parts
+/// of the game can react without polling the board state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A piece locked, clearing `lines` lines. `t_spin` is set when a T piece
+    /// was rotated into a spot with three of its four corners filled.
+    Locked {
+        shape: Shape,
+        lines: u32,
+        t_spin: bool,
+    },
+    /// A line clear left the board completely empty.
+    AllClear { lines: u32 },
+    /// A line clear sends `lines` rows of garbage to another board, after
+    /// cancelling out the garbage waiting to rise on this one.
+    Attack { lines: u32 },
+}
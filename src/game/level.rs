@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Rows fall once per this long at level 1.
+const BASE_GRAVITY: Duration = Duration::from_millis(500);
+/// Gravity stops speeding up past this level, as in the guideline.
+const MAX_GRAVITY_LEVEL: u32 = 20;
+/// Shortest time between rows falling, by gravity or soft drop, so the game
+/// loop never waits for nothing.
+pub const MIN_FALL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How many lines it takes to reach the next level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Goal {
+    /// 10 lines per level, however they are cleared.
+    Fixed,
+    /// 5 lines times the level, with bigger clears counting for more.
+    Variable,
+}
+
+impl Goal {
+    /// Lines needed to get through `level`.
+    pub fn lines_for_level(self, level: u32) -> u32 {
+        match self {
+            Goal::Fixed => 10,
+            Goal::Variable => 5 * level,
+        }
+    }
+
+    /// How many lines towards the goal a clear of `lines` counts as.
+    pub fn lines_awarded(self, lines: u32, t_spin: bool) -> u32 {
+        match self {
+            Goal::Fixed => lines,
+            Goal::Variable if t_spin => [4, 8, 12, 16][lines.min(3) as usize],
+            Goal::Variable => [0, 1, 3, 5, 8][lines.min(4) as usize],
+        }
+    }
+}
+
+/// Time between rows falling at `level`, following the guideline curve.
+pub fn gravity(level: u32) -> Duration {
+    let steps = level.clamp(1, MAX_GRAVITY_LEVEL) as f64 - 1.0;
+    BASE_GRAVITY
+        .mul_f64((0.8 - steps * 0.007).powf(steps))
+        .max(MIN_FALL_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goals() {
+        assert_eq!(Goal::Fixed.lines_for_level(7), 10);
+        assert_eq!(Goal::Fixed.lines_awarded(4, false), 4);
+        assert_eq!(Goal::Variable.lines_for_level(7), 35);
+        assert_eq!(Goal::Variable.lines_awarded(4, false), 8);
+        assert_eq!(Goal::Variable.lines_awarded(2, true), 12);
+
+        assert_eq!(gravity(1), BASE_GRAVITY);
+        assert!(gravity(10) < gravity(9));
+        assert_eq!(gravity(115), gravity(MAX_GRAVITY_LEVEL));
+        assert!(gravity(u32::MAX) >= MIN_FALL_INTERVAL);
+    }
+}
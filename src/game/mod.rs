@@ -1,2 +1,11 @@
+pub mod bot;
+pub mod event;
+pub mod level;
+pub mod objective;
+pub mod piece_set;
+pub mod randomizer;
+pub mod rewind;
+pub mod royale;
 pub mod shape;
 pub mod tetris;
+pub mod view;
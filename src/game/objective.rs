@@ -0,0 +1,169 @@
+use rand::seq::SliceRandom;
+
+use super::{event::GameEvent, shape::Shape};
+
+/// Pieces locked before the first objective appears, and between one being
+/// completed and the next.
+const PIECES_BETWEEN: u32 = 10;
+
+/// A goal set during a game, checked against the events the board emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Clear `lines` lines with one piece, of a given shape if set.
+    Clear {
+        lines: u32,
+        shape: Option<Shape>,
+    },
+    TSpin,
+    AllClear,
+}
+
+impl Objective {
+    const ALL: [Objective; 6] = [
+        Objective::Clear {
+            lines: 2,
+            shape: Some(Shape::I),
+        },
+        Objective::Clear {
+            lines: 2,
+            shape: Some(Shape::T),
+        },
+        Objective::Clear {
+            lines: 3,
+            shape: None,
+        },
+        Objective::Clear {
+            lines: 4,
+            shape: None,
+        },
+        Objective::TSpin,
+        Objective::AllClear,
+    ];
+
+    /// Whether the objective can be met, as the named shapes and T-spins
+    /// only exist in the standard piece set.
+    fn is_possible(self, standard_pieces: bool) -> bool {
+        match self {
+            Objective::Clear { shape: None, .. } | Objective::AllClear => true,
+            Objective::Clear { shape: Some(_), .. } | Objective::TSpin => standard_pieces,
+        }
+    }
+
+    /// Points awarded for completing the objective.
+    pub fn bonus(self) -> u32 {
+        match self {
+            Objective::Clear { lines, .. } => 250 * lines,
+            Objective::TSpin => 1000,
+            Objective::AllClear => 2000,
+        }
+    }
+
+    fn is_met_by(self, event: &GameEvent) -> bool {
+        match (self, *event) {
+            (
+                Objective::Clear { lines, shape },
+                GameEvent::Locked {
+                    shape: locked,
+                    lines: cleared,
+                    ..
+                },
+            ) => cleared == lines && shape.iter().all(|&shape| shape == locked),
+            (Objective::TSpin, GameEvent::Locked { t_spin, .. }) => t_spin,
+            (Objective::AllClear, GameEvent::AllClear { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Hands out an objective every few pieces and keeps track of the ones done.
+#[derive(Clone)]
+pub struct Objectives {
+    current: Option<Objective>,
+    /// Pieces to lock before the next objective is handed out.
+    countdown: u32,
+    completed: u32,
+    /// Whether the game is played with the standard piece set.
+    standard_pieces: bool,
+}
+
+impl Objectives {
+    pub fn new(standard_pieces: bool) -> Self {
+        Self {
+            current: None,
+            countdown: PIECES_BETWEEN,
+            completed: 0,
+            standard_pieces,
+        }
+    }
+
+    pub fn current(&self) -> Option<Objective> {
+        self.current
+    }
+
+    pub fn completed(&self) -> u32 {
+        self.completed
+    }
+
+    /// Checks `event` against the current objective, returning the objective
+    /// if it was just completed.
+    pub fn handle(&mut self, event: &GameEvent) -> Option<Objective> {
+        match self.current {
+            Some(objective) if objective.is_met_by(event) => {
+                self.current = None;
+                self.countdown = PIECES_BETWEEN;
+                self.completed += 1;
+                return Some(objective);
+            }
+            Some(_) => {}
+            None => {
+                if let GameEvent::Locked { .. } = event {
+                    self.countdown = self.countdown.saturating_sub(1);
+                    if self.countdown == 0 {
+                        let possible: Vec<Objective> = Objective::ALL
+                            .into_iter()
+                            .filter(|objective| objective.is_possible(self.standard_pieces))
+                            .collect();
+                        self.current = possible.choose(&mut rand::thread_rng()).copied();
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked(shape: Shape, lines: u32) -> GameEvent {
+        GameEvent::Locked {
+            shape,
+            lines,
+            t_spin: false,
+        }
+    }
+
+    #[test]
+    fn test_objectives() {
+        let mut objectives = Objectives::new(true);
+
+        for _ in 0..PIECES_BETWEEN {
+            assert_eq!(objectives.current(), None);
+            assert_eq!(objectives.handle(&locked(Shape::O, 0)), None);
+        }
+        assert!(objectives.current().is_some());
+
+        let objective = Objective::Clear {
+            lines: 2,
+            shape: Some(Shape::I),
+        };
+        objectives.current = Some(objective);
+
+        assert_eq!(objectives.handle(&locked(Shape::T, 2)), None);
+        assert_eq!(objectives.handle(&locked(Shape::I, 1)), None);
+        assert_eq!(objectives.handle(&locked(Shape::I, 2)), Some(objective));
+        assert_eq!(objectives.current(), None);
+        assert_eq!(objectives.completed(), 1);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::shape::Shape;
+use crate::error::{Error, Result};
+
+/// Most kinds of piece a set can have.
+pub const MAX_SHAPES: usize = 32;
+
+/// How a piece is nudged when a turn is blocked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KickTable {
+    /// The SRS kicks of the J, L, S, T and Z pieces.
+    #[default]
+    Srs,
+    /// The SRS kicks of the I piece.
+    I,
+    /// Turns in place or not at all.
+    None,
+}
+
+/// One kind of piece in a set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PieceDef {
+    /// Names the piece in messages, saves and the bot protocol.
+    pub name: String,
+    /// Cells in the spawn orientation, with the top-left corner of the
+    /// bounding box at the origin.
+    pub cells: Vec<(i32, i32)>,
+    /// Rotation centre in half cells: `[2, 2]` turns around cell (1, 1),
+    /// `[3, 3]` around the corner it shares with cell (2, 2).
+    pub pivot: (i32, i32),
+    /// Red, green and blue.
+    pub color: (u8, u8, u8),
+    #[serde(default)]
+    pub kicks: KickTable,
+    /// Whether locking it right after a turn into a tight spot counts as a
+    /// T-spin.
+    #[serde(default)]
+    pub t_spin: bool,
+}
+
+/// The kinds of piece a game is played with. Each [`Shape`] stands for its
+/// place in the set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PieceSet {
+    pub pieces: Vec<PieceDef>,
+}
+
+impl PieceSet {
+    /// The seven tetrominoes.
+    pub fn standard() -> Self {
+        let piece = |name: &str, cells: [(i32, i32); 4], pivot, color, kicks| PieceDef {
+            name: name.to_string(),
+            cells: cells.to_vec(),
+            pivot,
+            color,
+            kicks,
+            t_spin: name == "T",
+        };
+        #[rustfmt::skip]
+        let pieces = vec![
+            piece("I", [(0, 1), (1, 1), (2, 1), (3, 1)], (3, 3), (0, 255, 255), KickTable::I),
+            piece("O", [(1, 0), (2, 0), (1, 1), (2, 1)], (3, 1), (255, 255, 0), KickTable::None),
+            piece("T", [(1, 0), (0, 1), (1, 1), (2, 1)], (2, 2), (128, 0, 128), KickTable::Srs),
+            piece("J", [(0, 0), (0, 1), (1, 1), (2, 1)], (2, 2), (0, 255, 0), KickTable::Srs),
+            piece("L", [(2, 0), (0, 1), (1, 1), (2, 1)], (2, 2), (255, 0, 0), KickTable::Srs),
+            piece("S", [(1, 0), (2, 0), (0, 1), (1, 1)], (2, 2), (0, 0, 255), KickTable::Srs),
+            piece("Z", [(0, 0), (1, 0), (1, 1), (2, 1)], (2, 2), (255, 127, 0), KickTable::Srs),
+        ];
+        Self { pieces }
+    }
+
+    /// Reads a set of pieces from a TOML file with a `[[pieces]]` table for
+    /// each kind of piece.
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(Error::InvalidConfig(format!(
+                    "pieces: no piece set file at {:?}",
+                    path
+                )))
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let set: Self = toml::from_str(&contents)?;
+        set.validate()
+            .map_err(|reason| Error::InvalidConfig(format!("pieces: {}", reason)))?;
+        Ok(set)
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.pieces.is_empty() || self.pieces.len() > MAX_SHAPES {
+            return Err(format!(
+                "a piece set needs 1 to {} pieces, got {}",
+                MAX_SHAPES,
+                self.pieces.len()
+            ));
+        }
+        for (index, piece) in self.pieces.iter().enumerate() {
+            if piece.name.is_empty() {
+                return Err(format!("piece {} has no name", index + 1));
+            }
+            if self.pieces[..index]
+                .iter()
+                .any(|other| other.name.eq_ignore_ascii_case(&piece.name))
+            {
+                return Err(format!("more than one piece is called {:?}", piece.name));
+            }
+            if piece.cells.is_empty() || piece.cells.iter().any(|&(x, y)| x < 0 || y < 0) {
+                return Err(format!(
+                    "piece {:?} needs at least one cell, none of them negative",
+                    piece.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == Self::standard()
+    }
+
+    /// Every kind of piece in the set.
+    pub fn shapes(&self) -> Vec<Shape> {
+        Shape::all(self.pieces.len())
+    }
+
+    pub fn def(&self, shape: Shape) -> &PieceDef {
+        &self.pieces[shape.index()]
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        match shape {
+            Shape::GARBAGE => "garbage",
+            shape => &self.def(shape).name,
+        }
+    }
+
+    /// Red, green and blue of `shape`, garbage included.
+    pub fn color(&self, shape: Shape) -> (u8, u8, u8) {
+        match shape {
+            Shape::GARBAGE => (210, 210, 210),
+            shape => self.def(shape).color,
+        }
+    }
+
+    /// The piece called `name`, ignoring case.
+    pub fn find(&self, name: &str) -> Option<Shape> {
+        self.shapes()
+            .into_iter()
+            .find(|&shape| self.name(shape).eq_ignore_ascii_case(name))
+    }
+}
+
+impl Default for PieceSet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let set: PieceSet = toml::from_str(
+            r#"
+            [[pieces]]
+            name = "V"
+            cells = [[0, 0], [0, 1], [1, 1]]
+            pivot = [1, 1]
+            color = [255, 0, 255]
+
+            [[pieces]]
+            name = "I3"
+            cells = [[0, 1], [1, 1], [2, 1]]
+            pivot = [2, 2]
+            color = [0, 255, 255]
+            kicks = "i"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(set.validate(), Ok(()));
+        assert_eq!(set.pieces[0].kicks, KickTable::Srs);
+        assert_eq!(set.pieces[1].cells.len(), 3);
+        assert!(!set.is_standard());
+        assert!(PieceSet::standard().validate().is_ok());
+
+        let mut twice = set.clone();
+        twice.pieces[1].name = "v".to_string();
+        assert!(twice.validate().is_err());
+    }
+}
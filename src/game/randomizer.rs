@@ -0,0 +1,182 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::shape::Shape;
+
+/// Kinds of piece in the standard set.
+const STANDARD_SHAPES: usize = 7;
+
+/// Hands out the sequence of pieces a game is played with.
+pub trait PieceGenerator {
+    fn next_shape(&mut self) -> Shape;
+}
+
+/// Every piece is picked at random on its own, so droughts can be long.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RandomGenerator {
+    /// Kinds of piece in the set dealt from.
+    shapes: usize,
+}
+
+impl RandomGenerator {
+    pub fn new(shapes: usize) -> Self {
+        Self { shapes }
+    }
+}
+
+impl PieceGenerator for RandomGenerator {
+    fn next_shape(&mut self) -> Shape {
+        let index = rand::thread_rng().gen_range(0..self.shapes.max(1));
+        Shape::all(self.shapes)[index]
+    }
+}
+
+/// Deals every piece of the set in a shuffled bag before starting a new one,
+/// so with the seven tetrominoes no piece is ever more than 12 pieces away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BagGenerator {
+    shapes: usize,
+    bag: Vec<Shape>,
+}
+
+impl BagGenerator {
+    pub fn new(shapes: usize) -> Self {
+        Self {
+            shapes,
+            bag: vec![],
+        }
+    }
+}
+
+impl PieceGenerator for BagGenerator {
+    fn next_shape(&mut self) -> Shape {
+        if self.bag.is_empty() {
+            self.bag = Shape::all(self.shapes);
+            self.bag.shuffle(&mut rand::thread_rng());
+        }
+        self.bag.pop().expect("bag was just filled")
+    }
+}
+
+/// Which generator a game uses, as chosen in the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Randomizer {
+    Random,
+    Bag,
+}
+
+impl Randomizer {
+    pub fn name(self) -> &'static str {
+        match self {
+            Randomizer::Random => "random",
+            Randomizer::Bag => "bag",
+        }
+    }
+
+    /// A generator dealing from a set of `shapes` kinds of piece.
+    pub fn generator(self, shapes: usize) -> Generator {
+        match self {
+            Randomizer::Random => Generator::Random(RandomGenerator::new(shapes)),
+            Randomizer::Bag => Generator::Bag(BagGenerator::new(shapes)),
+        }
+    }
+}
+
+/// One of the generators, kept as an enum so a board can be saved with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Generator {
+    Random(RandomGenerator),
+    Bag(BagGenerator),
+}
+
+impl Generator {
+    pub fn randomizer(&self) -> Randomizer {
+        match self {
+            Generator::Random(_) => Randomizer::Random,
+            Generator::Bag(_) => Randomizer::Bag,
+        }
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Randomizer::Random.generator(STANDARD_SHAPES)
+    }
+}
+
+impl PieceGenerator for Generator {
+    fn next_shape(&mut self) -> Shape {
+        match self {
+            Generator::Random(generator) => generator.next_shape(),
+            Generator::Bag(generator) => generator.next_shape(),
+        }
+    }
+}
+
+/// Longest gap between two of the same piece tracked on its own, longer
+/// ones are counted together.
+pub const MAX_INTERVAL: usize = 15;
+
+/// How fairly a generator deals pieces over a long run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomizerStats {
+    pub pieces: u32,
+    /// How often each shape came up, indexed by [`Shape::index`].
+    pub counts: Vec<u32>,
+    /// Most pieces dealt in a row without each shape.
+    pub droughts: Vec<u32>,
+    /// `intervals[n]` counts pieces that came `n` pieces after the last one
+    /// of their shape, with the last entry counting everything longer.
+    pub intervals: [u32; MAX_INTERVAL + 1],
+}
+
+impl RandomizerStats {
+    /// Deals `pieces` pieces from `generator`, which deals from a set of
+    /// `shapes` kinds of piece, and tallies them up.
+    pub fn simulate<G: PieceGenerator>(generator: &mut G, shapes: usize, pieces: u32) -> Self {
+        let mut stats = Self {
+            pieces,
+            counts: vec![0; shapes],
+            droughts: vec![0; shapes],
+            intervals: [0; MAX_INTERVAL + 1],
+        };
+        let mut last_seen: Vec<Option<u32>> = vec![None; shapes];
+
+        for index in 0..pieces {
+            let shape = generator.next_shape().index();
+            stats.counts[shape] += 1;
+            if let Some(last) = last_seen[shape] {
+                let interval = index - last;
+                stats.intervals[(interval as usize).min(MAX_INTERVAL)] += 1;
+            }
+            last_seen[shape] = Some(index);
+
+            for (other, last) in last_seen.iter().enumerate() {
+                let without = index - last.unwrap_or(0) + u32::from(last.is_none());
+                stats.droughts[other] = stats.droughts[other].max(without);
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bag_deals_every_piece() {
+        let mut generator = BagGenerator::new(7);
+        let mut dealt: Vec<Shape> = (0..7).map(|_| generator.next_shape()).collect();
+        dealt.sort_by_key(|&shape| shape.index());
+        assert_eq!(dealt, Shape::all(7));
+
+        let stats = RandomizerStats::simulate(&mut generator, 7, 7000);
+        assert!(stats.counts.iter().all(|&count| count == 1000));
+        assert!(stats.droughts.iter().all(|&drought| drought <= 12));
+        assert_eq!(stats.intervals[0], 0);
+        assert_eq!(stats.intervals[MAX_INTERVAL], 0);
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// How far back a rewind goes at most.
+pub const REWIND_WINDOW: Duration = Duration::from_secs(5);
+/// Points a rewind costs.
+pub const REWIND_COST: u32 = 500;
+/// Most snapshots kept, so fast gravity cannot grow the buffer without end.
+const MAX_SNAPSHOTS: usize = 600;
+
+/// Recent copies of a game, so a mistake can be taken back.
+pub struct Rewind<T> {
+    snapshots: VecDeque<(Instant, T)>,
+}
+
+impl<T> Default for Rewind<T> {
+    fn default() -> Self {
+        Self {
+            snapshots: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> Rewind<T> {
+    /// Keeps `game` as it was at `now`, dropping copies older than the
+    /// rewind window.
+    pub fn record(&mut self, game: T, now: Instant) {
+        while let Some((taken, _)) = self.snapshots.front() {
+            if now.duration_since(*taken) <= REWIND_WINDOW && self.snapshots.len() < MAX_SNAPSHOTS {
+                break;
+            }
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((now, game));
+    }
+
+    /// Leaves the time the game spent paused out of the window.
+    pub fn pause(&mut self, paused: Duration) {
+        for (taken, _) in &mut self.snapshots {
+            *taken += paused;
+        }
+    }
+
+    /// The copy a rewind would go back to.
+    pub fn oldest(&self) -> Option<&T> {
+        self.snapshots.front().map(|(_, game)| game)
+    }
+
+    /// The oldest copy still in the window and how long before `now` it was
+    /// taken, forgetting every copy so the same stretch cannot be rewound
+    /// twice.
+    pub fn rewind(&mut self, now: Instant) -> Option<(T, Duration)> {
+        let (taken, game) = self.snapshots.pop_front()?;
+        self.snapshots.clear();
+        Some((game, now.saturating_duration_since(taken)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewind() {
+        let start = Instant::now();
+        let mut rewind = Rewind::default();
+        for second in 0..8 {
+            rewind.record(second, start + Duration::from_secs(second));
+        }
+
+        let now = start + Duration::from_secs(7);
+        assert_eq!(rewind.oldest(), Some(&2));
+        assert_eq!(rewind.rewind(now), Some((2, REWIND_WINDOW)));
+        assert_eq!(rewind.rewind(now), None);
+
+        // Time spent paused does not count
+        rewind.record(8, start + Duration::from_secs(8));
+        rewind.pause(Duration::from_secs(60));
+        rewind.record(9, start + Duration::from_secs(69));
+        assert_eq!(rewind.oldest(), Some(&8));
+
+        for index in 0..MAX_SNAPSHOTS as u64 + 10 {
+            rewind.record(index, start);
+        }
+        assert_eq!(rewind.rewind(start), Some((10, Duration::ZERO)));
+    }
+}
@@ -0,0 +1,150 @@
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+/// Most bots a battle royale can be played against.
+pub const MAX_BOTS: usize = 24;
+
+/// Attacks remembered for each board when targeting attackers.
+const ATTACKS_REMEMBERED: usize = 8;
+
+/// Who a board sends its garbage to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Targeting {
+    /// Any board still in play.
+    Random,
+    /// The boards that attacked this one last, or a random one if none of
+    /// them are left.
+    Attackers,
+    /// The board with the highest score.
+    Leader,
+}
+
+impl Targeting {
+    pub const ALL: [Targeting; 3] = [Targeting::Random, Targeting::Attackers, Targeting::Leader];
+}
+
+/// How a board is doing, for picking who to attack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Standing {
+    pub alive: bool,
+    pub score: u32,
+}
+
+/// Routes garbage between the boards of a battle royale. Board 0 is the
+/// player's, the rest are bots.
+#[derive(Debug, Clone)]
+pub struct Royale {
+    /// How each board picks its targets.
+    targeting: Vec<Targeting>,
+    /// Boards that attacked each board, latest last.
+    attackers: Vec<Vec<usize>>,
+    /// Board each board attacked last.
+    last_target: Vec<Option<usize>>,
+}
+
+impl Royale {
+    /// A royale against `bots` bots, each picking targets its own way, with
+    /// the player picking them by `targeting`.
+    pub fn new(bots: usize, targeting: Targeting) -> Self {
+        let mut rng = rand::thread_rng();
+        let bots = (0..bots).map(|_| *Targeting::ALL.choose(&mut rng).expect("not empty"));
+        Self::with_targeting(std::iter::once(targeting).chain(bots).collect())
+    }
+
+    fn with_targeting(targeting: Vec<Targeting>) -> Self {
+        let boards = targeting.len();
+        Self {
+            targeting,
+            attackers: vec![vec![]; boards],
+            last_target: vec![None; boards],
+        }
+    }
+
+    /// How the player picks targets.
+    pub fn targeting(&self) -> Targeting {
+        self.targeting[0]
+    }
+
+    pub fn set_targeting(&mut self, targeting: Targeting) {
+        self.targeting[0] = targeting;
+    }
+
+    pub fn last_target(&self, board: usize) -> Option<usize> {
+        self.last_target[board]
+    }
+
+    /// Picks who board `from` attacks and remembers the attack. `None` when
+    /// no other board is left.
+    pub fn attack(&mut self, from: usize, standings: &[Standing]) -> Option<usize> {
+        let open: Vec<usize> = (0..standings.len())
+            .filter(|&board| board != from && standings[board].alive)
+            .collect();
+
+        let target = match self.targeting[from] {
+            Targeting::Random => None,
+            Targeting::Attackers => self.attackers[from]
+                .iter()
+                .rev()
+                .copied()
+                .find(|board| open.contains(board)),
+            Targeting::Leader => open
+                .iter()
+                .copied()
+                .max_by_key(|&board| (standings[board].score, std::cmp::Reverse(board))),
+        }
+        .or_else(|| open.choose(&mut rand::thread_rng()).copied())?;
+
+        let attackers = &mut self.attackers[target];
+        attackers.retain(|&board| board != from);
+        attackers.push(from);
+        if attackers.len() > ATTACKS_REMEMBERED {
+            attackers.remove(0);
+        }
+        self.last_target[from] = Some(target);
+        Some(target)
+    }
+
+    /// Boards still in play.
+    pub fn remaining(standings: &[Standing]) -> usize {
+        standings.iter().filter(|standing| standing.alive).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standings(scores: &[u32]) -> Vec<Standing> {
+        scores
+            .iter()
+            .map(|&score| Standing { alive: true, score })
+            .collect()
+    }
+
+    #[test]
+    fn test_targeting() {
+        let mut royale = Royale::with_targeting(vec![
+            Targeting::Leader,
+            Targeting::Leader,
+            Targeting::Attackers,
+        ]);
+        let mut boards = standings(&[300, 100, 200]);
+
+        // The leader is the player, unless it is the player attacking
+        assert_eq!(royale.attack(1, &boards), Some(0));
+        assert_eq!(royale.attack(0, &boards), Some(2));
+        assert_eq!(royale.last_target(0), Some(2));
+
+        // Board 2 hits back at the player, who attacked it last
+        assert_eq!(royale.attack(2, &boards), Some(0));
+        // The player goes after whoever attacked last, skipping boards out
+        royale.set_targeting(Targeting::Attackers);
+        boards[2].alive = false;
+        assert_eq!(royale.attack(0, &boards), Some(1));
+
+        boards[1].alive = false;
+        assert_eq!(royale.attack(0, &boards), None);
+        assert_eq!(Royale::remaining(&boards), 1);
+    }
+}
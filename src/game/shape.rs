@@ -1,21 +1,38 @@
-use rand::{
-    distributions::{Distribution, Standard},
-    Rng,
-};
+use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, ops::Add};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum Shape {
-    I = 0,
-    O,
-    T,
-    J,
-    L,
-    S,
-    Z,
+use super::piece_set::{KickTable, PieceDef, PieceSet, MAX_SHAPES};
+
+/// A kind of piece, standing for its place in a [`PieceSet`]. The constants
+/// name the pieces of the standard set.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub struct Shape(u8);
+
+impl Shape {
+    pub const I: Shape = Shape(0);
+    pub const O: Shape = Shape(1);
+    pub const T: Shape = Shape(2);
+    pub const J: Shape = Shape(3);
+    pub const L: Shape = Shape(4);
+    pub const S: Shape = Shape(5);
+    pub const Z: Shape = Shape(6);
+    /// The blocks of garbage rows, which belong to no set.
+    pub const GARBAGE: Shape = Shape(u8::MAX);
+
+    /// Every kind of piece in a set of `count`.
+    pub fn all(count: usize) -> Vec<Shape> {
+        (0..count.min(MAX_SHAPES))
+            .map(|index| Shape(index as u8))
+            .collect()
+    }
+
+    /// Place of the shape in its set.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub struct Cell(pub i32, pub i32);
 
 impl Add for Cell {
@@ -32,81 +49,93 @@ impl From<(i32, i32)> for Cell {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Piece {
     shape: Shape,
     positions: HashSet<Cell>,
-    pivot: Cell,
+    /// Rotation centre in half cells, so I and O can turn around the middle
+    /// of a cell corner.
+    center: Cell,
+    /// Clockwise quarter turns from the spawn orientation, 0 to 3.
+    orientation: u8,
+    #[serde(default)]
+    kicks: KickTable,
+}
+
+/// SRS wall kicks tried in order when turning clockwise from each
+/// orientation, for every shape but I and O.
+#[rustfmt::skip]
+const KICKS: [[Cell; 5]; 4] = [
+    [Cell(0, 0), Cell(-1, 0), Cell(-1, -1), Cell(0, 2), Cell(-1, 2)],
+    [Cell(0, 0), Cell(1, 0), Cell(1, 1), Cell(0, -2), Cell(1, -2)],
+    [Cell(0, 0), Cell(1, 0), Cell(1, -1), Cell(0, 2), Cell(1, 2)],
+    [Cell(0, 0), Cell(-1, 0), Cell(-1, 1), Cell(0, -2), Cell(-1, -2)],
+];
+
+/// SRS wall kicks for the I piece turning clockwise.
+#[rustfmt::skip]
+const I_KICKS: [[Cell; 5]; 4] = [
+    [Cell(0, 0), Cell(-2, 0), Cell(1, 0), Cell(-2, 1), Cell(1, -2)],
+    [Cell(0, 0), Cell(-1, 0), Cell(2, 0), Cell(-1, -2), Cell(2, 1)],
+    [Cell(0, 0), Cell(2, 0), Cell(-1, 0), Cell(2, -1), Cell(-1, 2)],
+    [Cell(0, 0), Cell(1, 0), Cell(-2, 0), Cell(1, 2), Cell(-2, -1)],
+];
+
+/// Kicks tried in order when turning 180 degrees from each orientation, the
+/// SRS+ table used by modern clients. I shares it, O never needs a kick.
+#[rustfmt::skip]
+const KICKS_180: [[Cell; 6]; 4] = [
+    [Cell(0, 0), Cell(0, -1), Cell(1, -1), Cell(-1, -1), Cell(1, 0), Cell(-1, 0)],
+    [Cell(0, 0), Cell(1, 0), Cell(1, -2), Cell(1, -1), Cell(0, -2), Cell(0, -1)],
+    [Cell(0, 0), Cell(0, 1), Cell(-1, 1), Cell(1, 1), Cell(-1, 0), Cell(1, 0)],
+    [Cell(0, 0), Cell(-1, 0), Cell(-1, -2), Cell(-1, -1), Cell(0, -2), Cell(0, -1)],
+];
+
+/// Orientation and column given to pieces as they enter the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnRule {
+    /// Clockwise quarter turns for each shape, indexed by [`Shape::index`].
+    pub turns: [u8; MAX_SHAPES],
+    /// Columns right of the board centre to spawn at, or left if negative.
+    pub column_offset: i32,
 }
 
-impl Distribution<Piece> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Piece {
-        let shape = match rng.gen_range(0..=6) {
-            0 => Shape::I,
-            1 => Shape::O,
-            2 => Shape::T,
-            3 => Shape::J,
-            4 => Shape::L,
-            5 => Shape::S,
-            _ => Shape::Z,
-        };
-        Piece::new(shape)
+impl SpawnRule {
+    /// `shape` from `pieces` as it enters a board `board_width` wide.
+    pub fn spawn(&self, pieces: &PieceSet, shape: Shape, board_width: i32) -> Piece {
+        let def = pieces.def(shape);
+        // Centre the box from the origin to the rightmost cell in the spawn
+        // orientation, which for the tetrominoes gives the guideline columns
+        let box_width = def.cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let piece = Piece::new(shape, def).oriented(self.turns[shape.index()]);
+        &piece + Cell((board_width - box_width) / 2 + self.column_offset, 0)
     }
 }
 
 impl Piece {
-    pub fn new(shape: Shape) -> Self {
-        match shape {
-            Shape::I => Self::new_piece(
-                Shape::I,
-                [Cell(0, 0), Cell(1, 0), Cell(2, 0), Cell(3, 0)],
-                Cell(1, 0),
-            ),
-            Shape::O => Self::new_piece(
-                Shape::O,
-                [Cell(0, 0), Cell(1, 0), Cell(0, 1), Cell(1, 1)],
-                Cell(0, 0),
-            ),
-            Shape::T => Self::new_piece(
-                Shape::T,
-                [Cell(0, 0), Cell(1, 0), Cell(2, 0), Cell(1, 1)],
-                Cell(1, 0),
-            ),
-            Shape::J => Self::new_piece(
-                Shape::J,
-                [Cell(0, 0), Cell(0, 1), Cell(0, 2), Cell(-1, 2)],
-                Cell(0, 1),
-            ),
-            Shape::L => Self::new_piece(
-                Shape::L,
-                [Cell(0, 0), Cell(0, 1), Cell(0, 2), Cell(1, 2)],
-                Cell(0, 1),
-            ),
-            Shape::S => Self::new_piece(
-                Shape::S,
-                [Cell(0, 0), Cell(1, 0), Cell(0, 1), Cell(-1, 1)],
-                Cell(0, 0),
-            ),
-            Shape::Z => Self::new_piece(
-                Shape::Z,
-                [Cell(0, 0), Cell(-1, 0), Cell(0, 1), Cell(1, 1)],
-                Cell(0, 0),
-            ),
+    /// Creates a piece in its spawn orientation, laid out in its bounding
+    /// box with the top-left corner at the origin.
+    pub fn new(shape: Shape, def: &PieceDef) -> Self {
+        Self {
+            shape,
+            positions: def.cells.iter().map(|&cell| Cell::from(cell)).collect(),
+            center: Cell::from(def.pivot),
+            orientation: 0,
+            kicks: def.kicks,
         }
     }
 
-    fn new_piece(s: Shape, cells: [Cell; 4], p: Cell) -> Self {
+    /// Cells already locked into the stack, which no longer turn.
+    pub fn locked(shape: Shape, cells: impl IntoIterator<Item = Cell>) -> Self {
         Self {
-            shape: s,
+            shape,
             positions: cells.into_iter().collect(),
-            pivot: p,
+            center: Cell(0, 0),
+            orientation: 0,
+            kicks: KickTable::None,
         }
     }
 
-    pub fn random_piece() -> Self {
-        rand::random()
-    }
-
     pub fn shape(&self) -> Shape {
         self.shape
     }
@@ -123,20 +152,71 @@ impl Piece {
         self.positions.intersection(&other.positions).count() > 0
     }
 
-    //https://gamedev.stackexchange.com/a/17976
+    /// The cell the piece turns around. Only a whole cell for the three wide
+    /// shapes, I and O turn around a corner.
+    pub fn pivot(&self) -> Cell {
+        Cell(self.center.0 / 2, self.center.1 / 2)
+    }
+
+    /// Turns the piece a quarter clockwise around its centre, without any
+    /// wall kicks.
     pub fn rotate(&self) -> Self {
-        let Cell(a, b) = self.pivot;
+        let Cell(cx, cy) = self.center;
 
         Self {
             shape: self.shape,
+            // Work in half cells: (dx, dy) from the centre becomes (-dy, dx)
             positions: self
                 .iter_positions()
-                .map(|Cell(x, y)| Cell(a + b - y, b - a + x))
+                .map(|Cell(x, y)| Cell((cx + cy - 2 * y) / 2, (cy - cx + 2 * x) / 2))
                 .collect(),
-            pivot: self.pivot,
+            center: self.center,
+            orientation: (self.orientation + 1) % 4,
+            kicks: self.kicks,
+        }
+    }
+
+    /// Offsets to try, in order, after turning this piece clockwise with
+    /// [`Piece::rotate`].
+    pub fn kicks(&self) -> &'static [Cell] {
+        match self.kicks {
+            KickTable::None => &KICKS[0][..1],
+            KickTable::I => &I_KICKS[self.orientation as usize],
+            KickTable::Srs => &KICKS[self.orientation as usize],
+        }
+    }
+
+    /// Turns the piece half way around its centre, without any wall kicks.
+    pub fn rotate_180(&self) -> Self {
+        self.rotate().rotate()
+    }
+
+    /// Offsets to try, in order, after turning this piece with
+    /// [`Piece::rotate_180`].
+    pub fn kicks_180(&self) -> &'static [Cell] {
+        match self.kicks {
+            KickTable::None => &KICKS_180[0][..1],
+            KickTable::I | KickTable::Srs => &KICKS_180[self.orientation as usize],
         }
     }
 
+    /// Rotates `turns` times, then moves the piece down or up so its top
+    /// cells are on row 0.
+    pub fn oriented(&self, turns: u8) -> Self {
+        let mut piece = self.clone();
+        for _ in 0..turns % 4 {
+            piece = piece.rotate();
+        }
+
+        let top = piece.iter_positions().map(|cell| cell.1).min().unwrap_or(0);
+        &piece + Cell(0, -top)
+    }
+
+    /// Whether line clears have removed every cell of this piece.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
     pub fn remove_cell(&mut self, y: i32) {
         self.positions = self
             .positions
@@ -161,7 +241,9 @@ impl Add<Cell> for &Piece {
         Piece {
             shape: self.shape,
             positions: self.positions.iter().map(|&pos| pos + rhs).collect(),
-            pivot: self.pivot + rhs,
+            center: self.center + Cell(2 * rhs.0, 2 * rhs.1),
+            orientation: self.orientation,
+            kicks: self.kicks,
         }
     }
 }
@@ -170,32 +252,42 @@ impl Add<Cell> for &Piece {
 mod tests {
     use super::*;
 
+    fn standard(shape: Shape) -> Piece {
+        Piece::new(shape, PieceSet::standard().def(shape))
+    }
+
     #[test]
     fn test() {
-        let piece_i = Piece::new(Shape::I);
+        let piece_i = standard(Shape::I);
 
         assert_eq!(piece_i.shape, Shape::I);
 
         println!("{:#?}", piece_i);
     }
 
-    #[test]
-    fn test_random() {
-        let piece_random = Piece::random_piece();
-        println!("{:#?}", piece_random);
-    }
-
     #[test]
     fn test_rotate() {
-        let piece_i = Piece::new(Shape::I);
+        let piece_i = standard(Shape::I);
 
         let rotated_i = piece_i.rotate();
 
-        assert_eq!(rotated_i.pivot, piece_i.pivot);
+        assert_eq!(rotated_i.center, piece_i.center);
+        assert_eq!(rotated_i.orientation, 1);
 
         assert_eq!(
             rotated_i.positions,
-            [Cell(1, -1), Cell(1, 1), Cell(1, 0), Cell(1, 2)]
+            [Cell(2, 0), Cell(2, 1), Cell(2, 2), Cell(2, 3)]
+                .into_iter()
+                .collect::<HashSet<Cell>>()
+        );
+
+        let piece_o = standard(Shape::O);
+        assert_eq!(piece_o.rotate().positions, piece_o.positions);
+
+        let piece_t = standard(Shape::T);
+        assert_eq!(
+            piece_t.rotate().positions,
+            [Cell(1, 0), Cell(1, 1), Cell(2, 1), Cell(1, 2)]
                 .into_iter()
                 .collect::<HashSet<Cell>>()
         );
@@ -211,9 +303,35 @@ mod tests {
         ]
         .into_iter()
         {
-            let piece = Piece::new(shape);
+            let piece = standard(shape);
             let roated_piece = piece.rotate().rotate().rotate().rotate();
             assert_eq!(piece.positions, roated_piece.positions);
+            assert_eq!(roated_piece.orientation, 0);
         }
     }
+
+    #[test]
+    fn test_spawn_rule() {
+        let pieces = PieceSet::standard();
+        let mut rule = SpawnRule::default();
+        let piece_i = rule.spawn(&pieces, Shape::I, 10);
+
+        assert_eq!(
+            piece_i.positions,
+            [Cell(3, 0), Cell(4, 0), Cell(5, 0), Cell(6, 0)]
+                .into_iter()
+                .collect::<HashSet<Cell>>()
+        );
+
+        rule.turns[Shape::I.index()] = 1;
+        rule.column_offset = -2;
+        let piece_i = rule.spawn(&pieces, Shape::I, 10);
+
+        assert_eq!(
+            piece_i.positions,
+            [Cell(3, 0), Cell(3, 1), Cell(3, 2), Cell(3, 3)]
+                .into_iter()
+                .collect::<HashSet<Cell>>()
+        );
+    }
 }
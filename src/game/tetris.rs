@@ -1,6 +1,39 @@
-use std::{collections::HashSet, mem};
+use std::{
+    collections::{HashSet, VecDeque},
+    mem,
+    sync::Arc,
+};
 
-use super::shape::{Cell, Piece, Shape};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::event::GameEvent;
+use super::level::Goal;
+use super::piece_set::PieceSet;
+use super::randomizer::{Generator, PieceGenerator, Randomizer};
+use super::shape::{Cell, Piece, Shape, SpawnRule};
+use super::view::{ActivePiece, BoardView, ColumnStats, Stats};
+
+pub const BOARD_WIDTH: i32 = 10;
+pub const BOARD_HEIGHT: i32 = 20;
+
+/// Number of upcoming pieces shown in the preview.
+const QUEUE_LEN: usize = 3;
+
+/// Points for clearing 0 to 4 lines with one piece.
+const LINE_SCORES: [u32; 5] = [0, 100, 300, 500, 800];
+/// Points for each row a piece is soft dropped.
+const SOFT_DROP_SCORE: u32 = 1;
+/// Points for each row a piece is hard dropped.
+const HARD_DROP_SCORE: u32 = 2;
+/// Extra points when a clear of 0 to 4 lines leaves the board empty.
+const ALL_CLEAR_SCORES: [u32; 5] = [0, 800, 1200, 1800, 2000];
+/// Garbage rows sent for clearing 0 to 4 lines with one piece.
+const ATTACK_LINES: [u32; 5] = [0, 0, 1, 2, 4];
+/// Garbage rows sent for each line cleared with a T-spin.
+const T_SPIN_ATTACK: u32 = 2;
+/// Extra garbage rows sent for leaving the board empty.
+const ALL_CLEAR_ATTACK: u32 = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
@@ -11,56 +44,126 @@ pub enum Direction {
 pub trait Tetris {
     fn tick(&mut self);
     fn rotate(&mut self);
-    fn get(&self, cell: Cell) -> Option<Shape>;
+    fn rotate_180(&mut self);
+    fn view(&self) -> BoardView;
     fn shift(&mut self, direction: Direction);
+    /// Moves the piece down a row without locking it, returning whether it
+    /// moved.
+    fn soft_drop(&mut self) -> bool;
+    /// Drops the piece to the floor and locks it at once, returning whether
+    /// there was a piece to drop.
+    fn hard_drop(&mut self) -> bool;
+    /// Puts the piece in play aside and brings in the one held before, or
+    /// the next one if none was, returning whether it did. Only once per
+    /// piece.
+    fn hold(&mut self) -> bool;
     fn alive(&self) -> bool;
-    fn board_size(&self) -> (i32, i32);
+    /// Events since the last call, oldest first.
+    fn take_events(&mut self) -> Vec<GameEvent>;
+    /// Adds bonus points to the score.
+    fn award(&mut self, points: u32);
+    /// Takes points off the score, down to zero.
+    fn deduct(&mut self, points: u32);
+    /// Queues `lines` rows of garbage to rise from the floor before the next
+    /// piece spawns.
+    fn receive_garbage(&mut self, lines: u32);
 }
+
+/// Where the board is in its tick cycle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Phase {
+    /// A piece is in play and falls one row per tick.
+    Falling(Piece),
+    /// The last piece has locked and the next one spawns once the entry
+    /// delay (ARE) has run out.
+    Entry { remaining: u32 },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TetrisBoard {
     width: i32,
     height: i32,
-    current_piece: Piece,
+    phase: Phase,
     landed_pieces: Vec<Piece>,
+    queue: VecDeque<Shape>,
+    stats: Stats,
+    /// Column heights and holes, updated whenever a piece locks.
+    columns: Vec<ColumnStats>,
     alive: bool,
+    spawn_delay: u32,
+    spawn_rule: SpawnRule,
+    goal: Goal,
+    /// The kinds of piece dealt, which [`Shape`]s index into.
+    #[serde(default)]
+    pieces: Arc<PieceSet>,
+    #[serde(default)]
+    generator: Generator,
+    /// Whether the piece in play last moved by rotating, for T-spins.
+    last_rotated: bool,
+    /// Batches of garbage rows waiting to rise, oldest first.
+    #[serde(default)]
+    garbage: VecDeque<u32>,
+    /// The piece put aside with [`Tetris::hold`].
+    #[serde(default)]
+    held: Option<Shape>,
+    /// Whether the piece in play came out of hold, or was put there.
+    #[serde(default)]
+    hold_used: bool,
+    #[serde(skip)]
+    events: Vec<GameEvent>,
 }
 
 impl Tetris for TetrisBoard {
-    fn board_size(&self) -> (i32, i32) {
-        (self.width, self.height)
-    }
-
     fn tick(&mut self) {
         if !self.alive {
             return;
         }
 
-        let advanced_piece = &self.current_piece + Cell(0, 1);
+        let advanced_piece = match &mut self.phase {
+            Phase::Falling(piece) => &*piece + Cell(0, 1),
+            Phase::Entry { remaining } if *remaining > 1 => {
+                *remaining -= 1;
+                return;
+            }
+            Phase::Entry { .. } => {
+                self.spawn_piece();
+                return;
+            }
+        };
 
         if self.is_out_of_bounds(&advanced_piece) || self.is_colliding(&advanced_piece) {
-            let landed_piece = mem::replace(
-                &mut self.current_piece,
-                &Piece::random_piece() + Cell((self.width - 1) / 2, 0),
-            );
-
-            self.landed_pieces.push(landed_piece);
-            self.remove_full_lines();
-
-            if self.is_colliding(&self.current_piece) {
-                self.alive = false;
-            }
+            self.lock();
         } else {
-            self.current_piece = advanced_piece;
+            self.phase = Phase::Falling(advanced_piece);
+            self.last_rotated = false;
         }
     }
 
-    fn get(&self, cell: Cell) -> Option<Shape> {
-        if self.current_piece.has_position(cell) {
-            Some(self.current_piece.shape())
-        } else {
-            self.landed_pieces
-                .iter()
-                .find(|piece| piece.has_position(cell))
-                .map(|piece| piece.shape())
+    fn view(&self) -> BoardView {
+        let mut grid = vec![None; (self.width * self.height) as usize];
+        for piece in &self.landed_pieces {
+            for Cell(x, y) in piece.iter_positions() {
+                grid[(y * self.width + x) as usize] = Some(piece.shape());
+            }
+        }
+
+        let active = self.current_piece().map(|piece| ActivePiece {
+            shape: piece.shape(),
+            cells: piece.iter_positions().collect(),
+            ghost: self.drop_position(piece).iter_positions().collect(),
+        });
+
+        BoardView {
+            width: self.width,
+            height: self.height,
+            grid,
+            active,
+            queue: self.queue.iter().copied().collect(),
+            stats: self.stats,
+            columns: self.columns.clone(),
+            garbage: self.garbage.iter().sum(),
+            held: self.held,
+            pieces: self.pieces.clone(),
         }
     }
 
@@ -69,15 +172,81 @@ impl Tetris for TetrisBoard {
             return;
         }
 
-        let shifted_piece = &self.current_piece
-            + match direction {
-                Direction::Left => Cell(-1, 0),
-                Direction::Right => Cell(1, 0),
-            };
+        let shifted_piece = match self.current_piece() {
+            Some(piece) => {
+                piece
+                    + match direction {
+                        Direction::Left => Cell(-1, 0),
+                        Direction::Right => Cell(1, 0),
+                    }
+            }
+            None => return,
+        };
 
         if !self.is_out_of_bounds(&shifted_piece) && !self.is_colliding(&shifted_piece) {
-            self.current_piece = shifted_piece;
+            self.phase = Phase::Falling(shifted_piece);
+            self.last_rotated = false;
+        }
+    }
+
+    fn soft_drop(&mut self) -> bool {
+        if !self.alive {
+            return false;
+        }
+
+        let dropped_piece = match self.current_piece() {
+            Some(piece) => piece + Cell(0, 1),
+            None => return false,
+        };
+
+        if self.is_out_of_bounds(&dropped_piece) || self.is_colliding(&dropped_piece) {
+            return false;
+        }
+        self.phase = Phase::Falling(dropped_piece);
+        self.last_rotated = false;
+        self.stats.score += SOFT_DROP_SCORE;
+        true
+    }
+
+    fn hard_drop(&mut self) -> bool {
+        if !self.alive {
+            return false;
+        }
+
+        let (dropped_piece, rows) = match self.current_piece() {
+            Some(piece) => {
+                let dropped = self.drop_position(piece);
+                let rows = dropped.pivot().1 - piece.pivot().1;
+                (dropped, rows as u32)
+            }
+            None => return false,
+        };
+
+        if rows > 0 {
+            self.last_rotated = false;
         }
+        self.phase = Phase::Falling(dropped_piece);
+        self.stats.score += HARD_DROP_SCORE * rows;
+        self.lock();
+        true
+    }
+
+    fn hold(&mut self) -> bool {
+        if !self.alive || self.hold_used {
+            return false;
+        }
+
+        let shape = match self.current_piece() {
+            Some(piece) => piece.shape(),
+            None => return false,
+        };
+        let next = match self.held.replace(shape) {
+            Some(held) => held,
+            None => self.next_from_queue(),
+        };
+        self.enter(next);
+        self.hold_used = true;
+        true
     }
 
     fn rotate(&mut self) {
@@ -85,31 +254,393 @@ impl Tetris for TetrisBoard {
             return;
         }
 
-        let rotated_piece = self.current_piece.rotate();
+        if let Some(piece) = self.current_piece() {
+            let (rotated_piece, kicks) = (piece.rotate(), piece.kicks());
+            self.try_rotation(rotated_piece, kicks);
+        }
+    }
 
-        if !self.is_out_of_bounds(&rotated_piece) && !self.is_colliding(&rotated_piece) {
-            self.current_piece = rotated_piece;
+    fn rotate_180(&mut self) {
+        if !self.alive {
+            return;
+        }
+
+        if let Some(piece) = self.current_piece() {
+            let (rotated_piece, kicks) = (piece.rotate_180(), piece.kicks_180());
+            self.try_rotation(rotated_piece, kicks);
         }
     }
 
     fn alive(&self) -> bool {
         self.alive
     }
+
+    fn take_events(&mut self) -> Vec<GameEvent> {
+        mem::take(&mut self.events)
+    }
+
+    fn award(&mut self, points: u32) {
+        self.stats.score += points;
+    }
+
+    fn deduct(&mut self, points: u32) {
+        self.stats.score = self.stats.score.saturating_sub(points);
+    }
+
+    fn receive_garbage(&mut self, lines: u32) {
+        if lines > 0 {
+            self.garbage.push_back(lines);
+        }
+    }
 }
 
 impl TetrisBoard {
     fn new(width: i32, height: i32) -> Self {
+        let pieces = Arc::new(PieceSet::standard());
+        let mut generator = Generator::default();
         Self {
             width,
             height,
-            current_piece: &Piece::random_piece() + Cell((width - 1) / 2, 0),
+            phase: Phase::Falling(SpawnRule::default().spawn(
+                &pieces,
+                generator.next_shape(),
+                width,
+            )),
             landed_pieces: vec![],
+            queue: (0..QUEUE_LEN).map(|_| generator.next_shape()).collect(),
+            stats: Stats {
+                level: 1,
+                goal_remaining: Goal::Fixed.lines_for_level(1),
+                ..Stats::default()
+            },
+            columns: vec![ColumnStats::default(); width as usize],
             alive: true,
+            spawn_delay: 0,
+            spawn_rule: SpawnRule::default(),
+            goal: Goal::Fixed,
+            pieces,
+            generator,
+            last_rotated: false,
+            garbage: VecDeque::new(),
+            held: None,
+            hold_used: false,
+            events: vec![],
         }
     }
 
     pub fn new_default() -> Self {
-        Self::new(10, 20)
+        Self::new(BOARD_WIDTH, BOARD_HEIGHT)
+    }
+
+    /// Sets how many ticks pass between a piece locking and the next piece
+    /// spawning.
+    pub fn with_spawn_delay(mut self, ticks: u32) -> Self {
+        self.spawn_delay = ticks;
+        self
+    }
+
+    /// Sets how many lines it takes to level up.
+    pub fn with_goal(mut self, goal: Goal) -> Self {
+        self.goal = goal;
+        self.stats.goal_remaining = goal.lines_for_level(self.stats.level);
+        self
+    }
+
+    /// Deals pieces with `randomizer`, redealing the piece in play and the
+    /// queue.
+    pub fn with_randomizer(mut self, randomizer: Randomizer) -> Self {
+        self.generator = randomizer.generator(self.pieces.pieces.len());
+        self.redeal();
+        self
+    }
+
+    /// Plays with the pieces of `pieces`, redealing the piece in play and the
+    /// queue from it.
+    pub fn with_piece_set(mut self, pieces: Arc<PieceSet>) -> Self {
+        self.generator = self.generator.randomizer().generator(pieces.pieces.len());
+        self.pieces = pieces;
+        self.redeal();
+        self
+    }
+
+    fn redeal(&mut self) {
+        if let Phase::Falling(_) = self.phase {
+            let shape = self.generator.next_shape();
+            self.phase = Phase::Falling(self.spawn(shape));
+        }
+        self.queue = (0..QUEUE_LEN)
+            .map(|_| self.generator.next_shape())
+            .collect();
+    }
+
+    /// Fills the bottom of the board with `rows`, top row first. Each
+    /// character is a cell: `.` for empty, `#` for garbage or the shape it
+    /// is coloured as.
+    pub fn with_stack(mut self, rows: &[&str]) -> Self {
+        let top = self.height - rows.len() as i32;
+        for (y, row) in (top..).zip(rows) {
+            for (x, c) in (0..).zip(row.chars()) {
+                let shape = match c {
+                    '#' => Some(Shape::GARBAGE),
+                    c => self.pieces.find(&c.to_string()),
+                };
+                if let Some(shape) = shape {
+                    self.landed_pieces.push(Piece::locked(shape, [Cell(x, y)]));
+                }
+            }
+        }
+        self.columns = self.column_stats();
+        self
+    }
+
+    /// Deals `shapes` in order, starting with the piece in play, before
+    /// going back to the randomizer.
+    pub fn with_pieces(mut self, shapes: &[Shape]) -> Self {
+        let mut shapes = shapes.iter().copied();
+        if let Some(shape) = shapes.next() {
+            self.phase = Phase::Falling(self.spawn(shape));
+        }
+        self.queue = shapes.collect();
+        while self.queue.len() < QUEUE_LEN {
+            self.queue.push_back(self.generator.next_shape());
+        }
+        self
+    }
+
+    /// `shape` as it would enter this board.
+    pub fn spawn(&self, shape: Shape) -> Piece {
+        self.spawn_rule.spawn(&self.pieces, shape, self.width)
+    }
+
+    /// Sets the orientation and column new pieces spawn with, applying it to
+    /// the piece already in play.
+    pub fn with_spawn_rule(mut self, rule: SpawnRule) -> Self {
+        self.spawn_rule = rule;
+        if let Phase::Falling(piece) = &self.phase {
+            self.phase = Phase::Falling(self.spawn(piece.shape()));
+        }
+        self
+    }
+
+    /// The piece in play, or `None` while waiting for the next one to spawn.
+    pub fn current_piece(&self) -> Option<&Piece> {
+        match &self.phase {
+            Phase::Falling(piece) => Some(piece),
+            Phase::Entry { .. } => None,
+        }
+    }
+
+    fn spawn_piece(&mut self) {
+        self.raise_garbage();
+        if !self.alive {
+            return;
+        }
+        let next = self.next_from_queue();
+        self.enter(next);
+        self.hold_used = false;
+    }
+
+    /// Takes the next shape off the queue, topping it up.
+    fn next_from_queue(&mut self) -> Shape {
+        self.queue.push_back(self.generator.next_shape());
+        self.queue.pop_front().expect("queue is never empty")
+    }
+
+    /// Puts `shape` in play, ending the game if there is no room for it.
+    fn enter(&mut self, shape: Shape) {
+        let piece = self.spawn(shape);
+        if self.is_colliding(&piece) {
+            self.alive = false;
+        }
+        self.phase = Phase::Falling(piece);
+        self.last_rotated = false;
+    }
+
+    /// Locks the piece in play where it is, clears lines and moves on to
+    /// the next piece.
+    fn lock(&mut self) {
+        let entry = Phase::Entry {
+            remaining: self.spawn_delay,
+        };
+        let landed_piece = match mem::replace(&mut self.phase, entry) {
+            Phase::Falling(piece) => piece,
+            Phase::Entry { .. } => unreachable!("only a falling piece can land"),
+        };
+        let shape = landed_piece.shape();
+        let t_spin = self.is_t_spin(&landed_piece);
+        self.landed_pieces.push(landed_piece);
+        self.stats.pieces += 1;
+
+        let lines = self.remove_full_lines();
+        self.advance_goal(lines as u32, t_spin);
+        self.columns = self.column_stats();
+        self.stats.score += LINE_SCORES[lines];
+        self.events.push(GameEvent::Locked {
+            shape,
+            lines: lines as u32,
+            t_spin,
+        });
+        let all_clear = lines > 0 && self.landed_pieces.is_empty();
+        if all_clear {
+            self.stats.all_clears += 1;
+            self.stats.score += ALL_CLEAR_SCORES[lines];
+            self.events.push(GameEvent::AllClear {
+                lines: lines as u32,
+            });
+        }
+        self.attack(lines, t_spin, all_clear);
+
+        if self.spawn_delay == 0 {
+            self.spawn_piece();
+        }
+    }
+
+    /// Sends garbage for a clear of `lines` lines, first using it to cancel
+    /// out the garbage waiting to rise on this board.
+    fn attack(&mut self, lines: usize, t_spin: bool, all_clear: bool) {
+        let mut attack = if t_spin {
+            T_SPIN_ATTACK * lines as u32
+        } else {
+            ATTACK_LINES[lines]
+        };
+        if all_clear {
+            attack += ALL_CLEAR_ATTACK;
+        }
+
+        while let Some(waiting) = self.garbage.front_mut() {
+            if attack == 0 {
+                break;
+            }
+            let cancelled = attack.min(*waiting);
+            attack -= cancelled;
+            *waiting -= cancelled;
+            if *waiting == 0 {
+                self.garbage.pop_front();
+            }
+        }
+        if attack > 0 {
+            self.events.push(GameEvent::Attack { lines: attack });
+        }
+    }
+
+    /// Pushes the stack up by the waiting garbage, each batch a block of rows
+    /// with the same gap. Blocks pushed off the top end the game.
+    fn raise_garbage(&mut self) {
+        if self.garbage.is_empty() {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        while let Some(lines) = self.garbage.pop_front() {
+            let lines = (lines as i32).min(self.height);
+            let gap = rng.gen_range(0..self.width);
+            for piece in &mut self.landed_pieces {
+                *piece = &*piece + Cell(0, -lines);
+            }
+            let width = self.width;
+            let cells: Vec<Cell> = (self.height - lines..self.height)
+                .flat_map(|y| {
+                    (0..width)
+                        .filter(move |&x| x != gap)
+                        .map(move |x| Cell(x, y))
+                })
+                .collect();
+            self.landed_pieces
+                .push(Piece::locked(Shape::GARBAGE, cells));
+        }
+        if self
+            .landed_pieces
+            .iter()
+            .flat_map(|piece| piece.iter_positions())
+            .any(|cell| cell.1 < 0)
+        {
+            self.alive = false;
+        }
+        self.columns = self.column_stats();
+    }
+
+    /// Counts a clear towards the level goal, going up as many levels as it
+    /// is worth.
+    fn advance_goal(&mut self, lines: u32, t_spin: bool) {
+        let mut awarded = self.goal.lines_awarded(lines, t_spin);
+        while awarded >= self.stats.goal_remaining {
+            awarded -= self.stats.goal_remaining;
+            self.stats.level += 1;
+            self.stats.goal_remaining = self.goal.lines_for_level(self.stats.level);
+        }
+        self.stats.goal_remaining -= awarded;
+    }
+
+    fn column_stats(&self) -> Vec<ColumnStats> {
+        let filled: HashSet<Cell> = self
+            .landed_pieces
+            .iter()
+            .flat_map(|piece| piece.iter_positions())
+            .collect();
+
+        (0..self.width)
+            .map(
+                |x| match (0..self.height).find(|&y| filled.contains(&Cell(x, y))) {
+                    Some(top) => ColumnStats {
+                        height: (self.height - top) as u32,
+                        holes: (top..self.height)
+                            .filter(|&y| !filled.contains(&Cell(x, y)))
+                            .count() as u32,
+                    },
+                    None => ColumnStats::default(),
+                },
+            )
+            .collect()
+    }
+
+    /// Moves the rotated piece in play to the first kick that fits, leaving it
+    /// unrotated if none do.
+    fn try_rotation(&mut self, rotated_piece: Piece, kicks: &[Cell]) {
+        for &kick in kicks {
+            let kicked_piece = &rotated_piece + kick;
+            if !self.is_out_of_bounds(&kicked_piece) && !self.is_colliding(&kicked_piece) {
+                self.phase = Phase::Falling(kicked_piece);
+                self.last_rotated = true;
+                return;
+            }
+        }
+    }
+
+    /// Whether `piece` got into place with a T-spin: a T piece whose last move
+    /// was a rotation, with at least three of the corners around its pivot
+    /// blocked by walls, the floor or other blocks.
+    fn is_t_spin(&self, piece: &Piece) -> bool {
+        if !self.pieces.def(piece.shape()).t_spin || !self.last_rotated {
+            return false;
+        }
+
+        let Cell(x, y) = piece.pivot();
+        let blocked_corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+            .into_iter()
+            .filter(|&(dx, dy)| self.is_blocked(Cell(x + dx, y + dy)))
+            .count();
+        blocked_corners >= 3
+    }
+
+    fn is_blocked(&self, cell: Cell) -> bool {
+        cell.0 < 0
+            || cell.0 >= self.width
+            || cell.1 >= self.height
+            || self
+                .landed_pieces
+                .iter()
+                .any(|piece| piece.has_position(cell))
+    }
+
+    /// Where `piece` would land if dropped straight down.
+    fn drop_position(&self, piece: &Piece) -> Piece {
+        let mut dropped = piece.clone();
+        loop {
+            let advanced = &dropped + Cell(0, 1);
+            if self.is_out_of_bounds(&advanced) || self.is_colliding(&advanced) {
+                return dropped;
+            }
+            dropped = advanced;
+        }
     }
 
     fn is_out_of_bounds(&self, piece: &Piece) -> bool {
@@ -140,11 +671,338 @@ impl TetrisBoard {
         }
     }
 
-    fn remove_full_lines(&mut self) {
+    /// Clears full lines, returning how many there were. Pieces left without
+    /// any cells are dropped, so an empty board has no landed pieces.
+    fn remove_full_lines(&mut self) -> usize {
+        let mut lines = 0;
         for y in 0..self.height {
             if self.is_line_full(y) {
                 self.remove_line(y);
+                lines += 1;
             }
         }
+
+        self.landed_pieces.retain(|piece| !piece.is_empty());
+        self.stats.lines += lines as u32;
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::piece_set::{KickTable, PieceDef};
+
+    fn standard(shape: Shape) -> Piece {
+        Piece::new(shape, PieceSet::standard().def(shape))
+    }
+
+    fn tick_until_locked(board: &mut TetrisBoard) {
+        let landed = board.stats.pieces;
+        while board.stats.pieces == landed {
+            board.tick();
+        }
+    }
+
+    #[test]
+    fn test_spawn_without_delay() {
+        let mut board = TetrisBoard::new_default();
+
+        tick_until_locked(&mut board);
+
+        assert!(board.current_piece().is_some());
+    }
+
+    #[test]
+    fn test_spawn_delay() {
+        let mut board = TetrisBoard::new_default().with_spawn_delay(3);
+
+        tick_until_locked(&mut board);
+
+        for _ in 0..3 {
+            assert!(board.current_piece().is_none());
+            board.tick();
+        }
+        assert!(board.current_piece().is_some());
+    }
+
+    #[test]
+    fn test_rotate_kicks_off_wall() {
+        let mut board = TetrisBoard::new_default();
+        board.phase = Phase::Falling(&standard(Shape::I).oriented(1) + Cell(-2, 5));
+        assert!(board
+            .current_piece()
+            .unwrap()
+            .iter_positions()
+            .all(|cell| cell.0 == 0));
+
+        // Turning flat against the left wall only works with a kick to the right
+        board.rotate();
+        let mut columns: Vec<i32> = board
+            .current_piece()
+            .unwrap()
+            .iter_positions()
+            .map(|cell| cell.0)
+            .collect();
+        columns.sort_unstable();
+        assert_eq!(columns, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rotate_180_kicks_off_floor() {
+        let mut board = TetrisBoard::new_default();
+        board.phase = Phase::Falling(&standard(Shape::T) + Cell(3, 18));
+
+        // Pointing down would go through the floor, so it is kicked up a row
+        board.rotate_180();
+
+        let piece = board.current_piece().unwrap();
+        for cell in [Cell(3, 18), Cell(4, 18), Cell(5, 18), Cell(4, 19)] {
+            assert!(piece.has_position(cell));
+        }
+    }
+
+    #[test]
+    fn test_soft_drop_does_not_lock() {
+        let mut board = TetrisBoard::new_default();
+        board.phase = Phase::Falling(board.spawn(Shape::T));
+
+        while board.soft_drop() {}
+
+        assert_eq!(board.stats.pieces, 0);
+        assert_eq!(board.stats.score, 18);
+        let piece = board.current_piece().unwrap();
+        assert!(piece.iter_positions().any(|cell| cell.1 == 19));
+
+        board.tick();
+        assert_eq!(board.stats.pieces, 1);
+    }
+
+    #[test]
+    fn test_hard_drop() {
+        let mut board = TetrisBoard::new_default().with_pieces(&[Shape::T, Shape::O]);
+
+        assert!(board.hard_drop());
+
+        assert_eq!(board.stats.pieces, 1);
+        assert_eq!(board.stats.score, 36);
+        assert_eq!(board.view().locked(Cell(4, 19)), Some(Shape::T));
+        assert_eq!(board.current_piece().unwrap().shape(), Shape::O);
+        assert!(matches!(
+            board.take_events()[..],
+            [GameEvent::Locked { lines: 0, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_hold() {
+        let mut board = TetrisBoard::new_default().with_pieces(&[Shape::T, Shape::O, Shape::I]);
+
+        // The first hold brings in the next piece, and only works once
+        assert!(board.hold());
+        assert_eq!(board.view().held, Some(Shape::T));
+        assert_eq!(board.current_piece().unwrap().shape(), Shape::O);
+        assert!(!board.hold());
+
+        // Once the O locks, holding swaps the T back in
+        board.hard_drop();
+        assert!(board.hold());
+        assert_eq!(board.view().held, Some(Shape::I));
+        assert_eq!(board.current_piece().unwrap().shape(), Shape::T);
+    }
+
+    #[test]
+    fn test_all_clear() {
+        let mut board = TetrisBoard::new_default();
+        // Fill the bottom row except where a flat I piece will land, using
+        // the bottom halves of O pieces
+        for x in [0, 1, 7, 8] {
+            let piece = &standard(Shape::O) + Cell(x - 1, 18);
+            board.landed_pieces.push(piece);
+        }
+        board.remove_line(18);
+        board.phase = Phase::Falling(board.spawn(Shape::I));
+
+        tick_until_locked(&mut board);
+
+        assert!(board.landed_pieces.is_empty());
+        assert_eq!(board.stats.lines, 1);
+        assert_eq!(board.stats.all_clears, 1);
+        assert_eq!(board.stats.score, LINE_SCORES[1] + ALL_CLEAR_SCORES[1]);
+        assert_eq!(
+            board.take_events(),
+            [
+                GameEvent::Locked {
+                    shape: Shape::I,
+                    lines: 1,
+                    t_spin: false
+                },
+                GameEvent::AllClear { lines: 1 },
+                GameEvent::Attack {
+                    lines: ALL_CLEAR_ATTACK
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_level_goal() {
+        let mut board = TetrisBoard::new_default();
+        board.advance_goal(4, false);
+        board.advance_goal(4, false);
+        assert_eq!((board.stats.level, board.stats.goal_remaining), (1, 2));
+        board.advance_goal(3, false);
+        assert_eq!((board.stats.level, board.stats.goal_remaining), (2, 9));
+
+        let mut board = TetrisBoard::new_default().with_goal(Goal::Variable);
+        assert_eq!(board.stats.goal_remaining, 5);
+        // A tetris is worth 8 lines, getting through level 1 and 3 into level 2
+        board.advance_goal(4, false);
+        assert_eq!((board.stats.level, board.stats.goal_remaining), (2, 7));
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let mut board = TetrisBoard::new_default();
+        // A flat I one row off the floor, leaving a hole under each cell
+        board.landed_pieces.push(&standard(Shape::I) + Cell(0, 17));
+
+        let columns = board.column_stats();
+
+        assert_eq!(columns.len(), 10);
+        for column in &columns[0..4] {
+            assert_eq!(
+                *column,
+                ColumnStats {
+                    height: 2,
+                    holes: 1
+                }
+            );
+        }
+        assert_eq!(columns[4], ColumnStats::default());
+    }
+
+    #[test]
+    fn test_t_spin() {
+        for last_rotated in [true, false] {
+            let mut board = TetrisBoard::new_default();
+            // A T pointing right against the left wall, with a block under
+            // its right corner
+            board.landed_pieces.push(&standard(Shape::I) + Cell(1, 18));
+            let piece = &standard(Shape::T).rotate() + Cell(-1, 17);
+            board.phase = Phase::Falling(piece);
+            board.last_rotated = last_rotated;
+
+            board.tick();
+
+            assert_eq!(
+                board.take_events(),
+                [GameEvent::Locked {
+                    shape: Shape::T,
+                    lines: 0,
+                    t_spin: last_rotated
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn test_view() {
+        let mut board = TetrisBoard::new_default();
+        let view = board.view();
+
+        assert_eq!(view.grid.len(), 200);
+        assert!(view.grid.iter().all(|cell| cell.is_none()));
+        assert_eq!(view.queue.len(), QUEUE_LEN);
+
+        let active = view.active.unwrap();
+        assert_eq!(active.cells.len(), 4);
+        assert!(active.ghost.iter().any(|cell| cell.1 == view.height - 1));
+
+        let next = view.queue[0];
+        tick_until_locked(&mut board);
+        let view = board.view();
+
+        assert_eq!(view.stats.pieces, 1);
+        assert_eq!(view.active.as_ref().unwrap().shape, next);
+        assert_eq!(view.grid.iter().filter(|cell| cell.is_some()).count(), 4);
+        for cell in active.ghost {
+            assert!(view.locked(cell).is_some());
+        }
+    }
+
+    #[test]
+    fn test_garbage() {
+        let mut board = TetrisBoard::new_default().with_pieces(&[Shape::O]);
+        board.receive_garbage(2);
+        assert_eq!(board.view().garbage, 2);
+
+        // It rises under the O once it locks, leaving one gap in each row
+        tick_until_locked(&mut board);
+        let view = board.view();
+        assert_eq!(view.garbage, 0);
+        for y in [18, 19] {
+            let row = (0..10).filter_map(|x| view.locked(Cell(x, y)));
+            assert_eq!(row.filter(|&shape| shape == Shape::GARBAGE).count(), 9);
+        }
+        assert_eq!(view.locked(Cell(4, 17)), Some(Shape::O));
+
+        // A tetris cancels the row waiting and sends the rest on
+        board.receive_garbage(1);
+        board.take_events();
+        board.attack(4, false, false);
+        assert_eq!(board.take_events(), [GameEvent::Attack { lines: 3 }]);
+        assert_eq!(board.view().garbage, 0);
+
+        // Garbage pushing the stack out of the top ends the game
+        board.receive_garbage(20);
+        tick_until_locked(&mut board);
+        assert!(!board.alive());
+    }
+
+    #[test]
+    fn test_custom_piece_set() {
+        let def = |name: &str, cells: Vec<(i32, i32)>| PieceDef {
+            name: name.to_string(),
+            cells,
+            pivot: (2, 2),
+            color: (255, 255, 255),
+            kicks: KickTable::None,
+            t_spin: false,
+        };
+        let pieces = Arc::new(PieceSet {
+            pieces: vec![
+                def("I5", vec![(0, 1), (1, 1), (2, 1), (3, 1), (4, 1)]),
+                def("V", vec![(0, 0), (0, 1), (1, 1)]),
+            ],
+        });
+        let (line, v) = (Shape::all(2)[0], Shape::all(2)[1]);
+        let mut board = TetrisBoard::new_default()
+            .with_piece_set(pieces)
+            .with_pieces(&[line, line]);
+        assert!(board.view().queue.iter().all(|shape| shape.index() < 2));
+
+        // Five wide, so it spawns in the middle five columns
+        let mut columns: Vec<i32> = board
+            .current_piece()
+            .unwrap()
+            .iter_positions()
+            .map(|cell| cell.0)
+            .collect();
+        columns.sort_unstable();
+        assert_eq!(columns, vec![2, 3, 4, 5, 6]);
+
+        // Two of them side by side fill a row
+        for direction in [Direction::Left, Direction::Right] {
+            for _ in 0..3 {
+                board.shift(direction);
+            }
+            tick_until_locked(&mut board);
+        }
+        assert_eq!(board.stats.lines, 1);
+        assert_eq!(board.stats.all_clears, 1);
+
+        let board = board.with_stack(&["V........."]);
+        assert_eq!(board.view().locked(Cell(0, 19)), Some(v));
     }
 }
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use super::piece_set::PieceSet;
+use super::shape::{Cell, Shape};
+
+/// Running totals for the current game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub score: u32,
+    pub lines: u32,
+    pub pieces: u32,
+    /// Line clears that left the board completely empty.
+    pub all_clears: u32,
+    pub level: u32,
+    /// Lines still to clear to reach the next level.
+    pub goal_remaining: u32,
+}
+
+/// How tall one column's stack is and how many empty cells are buried in it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub height: u32,
+    pub holes: u32,
+}
+
+/// The piece in play and where it would land if dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivePiece {
+    pub shape: Shape,
+    pub cells: Vec<Cell>,
+    pub ghost: Vec<Cell>,
+}
+
+/// An immutable snapshot of everything needed to draw or analyse a board,
+/// taken in one call instead of querying the board cell by cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardView {
+    pub width: i32,
+    pub height: i32,
+    /// Locked cells in row-major order, without the active piece.
+    pub grid: Vec<Option<Shape>>,
+    /// `None` while waiting for the next piece to spawn.
+    pub active: Option<ActivePiece>,
+    /// Upcoming pieces, next one first.
+    pub queue: Vec<Shape>,
+    pub stats: Stats,
+    /// Stack shape of each column as of the last lock, left to right.
+    pub columns: Vec<ColumnStats>,
+    /// Rows of garbage waiting to rise.
+    pub garbage: u32,
+    /// The piece put aside to play later.
+    pub held: Option<Shape>,
+    /// The set the shapes above belong to.
+    pub pieces: Arc<PieceSet>,
+}
+
+impl BoardView {
+    pub fn name(&self, shape: Shape) -> &str {
+        self.pieces.name(shape)
+    }
+
+    /// The locked block at `cell`, if any.
+    pub fn locked(&self, cell: Cell) -> Option<Shape> {
+        if 0 <= cell.0 && cell.0 < self.width && 0 <= cell.1 && cell.1 < self.height {
+            self.grid[(cell.1 * self.width + cell.0) as usize]
+        } else {
+            None
+        }
+    }
+
+    /// The block shown at `cell`, counting the active piece but not its ghost.
+    pub fn get(&self, cell: Cell) -> Option<Shape> {
+        match &self.active {
+            Some(active) if active.cells.contains(&cell) => Some(active.shape),
+            _ => self.locked(cell),
+        }
+    }
+
+    pub fn is_ghost(&self, cell: Cell) -> bool {
+        self.active
+            .iter()
+            .any(|active| active.ghost.contains(&cell))
+    }
+}
+
+/// Serializes with every shape written as its name in the set, as the bot
+/// protocol expects.
+impl Serialize for BoardView {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Active<'a> {
+            shape: &'a str,
+            cells: &'a [Cell],
+            ghost: &'a [Cell],
+        }
+
+        #[derive(Serialize)]
+        struct View<'a> {
+            width: i32,
+            height: i32,
+            grid: Vec<Option<&'a str>>,
+            active: Option<Active<'a>>,
+            queue: Vec<&'a str>,
+            stats: Stats,
+            columns: &'a [ColumnStats],
+            garbage: u32,
+            held: Option<&'a str>,
+        }
+
+        View {
+            width: self.width,
+            height: self.height,
+            grid: self
+                .grid
+                .iter()
+                .map(|cell| cell.map(|shape| self.name(shape)))
+                .collect(),
+            active: self.active.as_ref().map(|active| Active {
+                shape: self.name(active.shape),
+                cells: &active.cells,
+                ghost: &active.ghost,
+            }),
+            queue: self.queue.iter().map(|&shape| self.name(shape)).collect(),
+            stats: self.stats,
+            columns: &self.columns,
+            garbage: self.garbage,
+            held: self.held.map(|shape| self.name(shape)),
+        }
+        .serialize(serializer)
+    }
+}
@@ -0,0 +1,272 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use crossterm::event::KeyCode;
+
+use crate::config::SoftDrop;
+
+/// Something the player can do in game, bound to one or more keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Rotate,
+    Rotate180,
+    SoftDrop,
+    HardDrop,
+    Hold,
+    ZoomIn,
+    ZoomOut,
+    ToggleDebug,
+    ToggleAnalysis,
+    ToggleRandomizerStats,
+    Rewind,
+    Quit,
+}
+
+impl Action {
+    const ALL: [(Action, &'static str); 14] = [
+        (Action::MoveLeft, "move_left"),
+        (Action::MoveRight, "move_right"),
+        (Action::Rotate, "rotate"),
+        (Action::Rotate180, "rotate_180"),
+        (Action::SoftDrop, "soft_drop"),
+        (Action::HardDrop, "hard_drop"),
+        (Action::Hold, "hold"),
+        (Action::ZoomIn, "zoom_in"),
+        (Action::ZoomOut, "zoom_out"),
+        (Action::ToggleDebug, "toggle_debug"),
+        (Action::ToggleAnalysis, "toggle_analysis"),
+        (Action::ToggleRandomizerStats, "toggle_randomizer_stats"),
+        (Action::Rewind, "rewind"),
+        (Action::Quit, "quit"),
+    ];
+}
+
+impl Action {
+    /// The name the action goes by in the config.
+    pub fn name(self) -> &'static str {
+        Action::ALL
+            .into_iter()
+            .find(|&(action, _)| action == self)
+            .map(|(_, name)| name)
+            .unwrap_or_default()
+    }
+
+    /// Whether the action moves the piece, as opposed to changing the view
+    /// or leaving the game.
+    pub fn is_move(self) -> bool {
+        matches!(
+            self,
+            Action::MoveLeft
+                | Action::MoveRight
+                | Action::Rotate
+                | Action::Rotate180
+                | Action::SoftDrop
+                | Action::HardDrop
+                | Action::Hold
+        )
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|(_, name)| *name == s)
+            .map(|(action, _)| action)
+            .ok_or_else(|| format!("unknown action {:?}", s))
+    }
+}
+
+/// Parses a key name such as `a`, `left`, `space` or `f3`.
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let lower = name.to_ascii_lowercase();
+    let key = match lower.as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "esc" => KeyCode::Esc,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {:?}", name)),
+                },
+            }
+        }
+    };
+    Ok(key)
+}
+
+/// Which action each key triggers.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    keys: HashMap<KeyCode, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = [
+            (KeyCode::Left, Action::MoveLeft),
+            (KeyCode::Right, Action::MoveRight),
+            (KeyCode::Up, Action::Rotate),
+            (KeyCode::Char('a'), Action::Rotate180),
+            (KeyCode::Down, Action::SoftDrop),
+            (KeyCode::Char(' '), Action::HardDrop),
+            (KeyCode::Char('c'), Action::Hold),
+            (KeyCode::Char('+'), Action::ZoomIn),
+            (KeyCode::Char('='), Action::ZoomIn),
+            (KeyCode::Char('-'), Action::ZoomOut),
+            (KeyCode::F(3), Action::ToggleDebug),
+            (KeyCode::F(4), Action::ToggleAnalysis),
+            (KeyCode::F(5), Action::ToggleRandomizerStats),
+            (KeyCode::Backspace, Action::Rewind),
+            (KeyCode::Char('q'), Action::Quit),
+        ];
+        Self {
+            keys: keys.into_iter().collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// The default bindings with each action named in `overrides` moved to
+    /// the given key instead of its default ones, or unbound if the key is
+    /// `"none"`. A key can only do one thing, so binding one that another
+    /// action still uses is an error.
+    pub fn with_overrides(overrides: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut bindings = Self::default();
+        let mut moved = Vec::new();
+        for (action, key) in overrides {
+            let action: Action = action.parse()?;
+            bindings.keys.retain(|_, bound| *bound != action);
+            if key != "none" {
+                moved.push((parse_key(key)?, action));
+            }
+        }
+        for (key, action) in moved {
+            if let Some(bound) = bindings.keys.insert(key, action) {
+                return Err(format!(
+                    "{} is bound to both {} and {}",
+                    key_name(key),
+                    bound.name(),
+                    action.name()
+                ));
+            }
+        }
+        Ok(bindings)
+    }
+
+    /// The bindings for a board drawn mirrored, where the keys for moving
+    /// left and right still move the piece that way on screen. Rotation is
+    /// left alone, so pieces turn the other way on screen.
+    pub fn mirrored(mut self) -> Self {
+        for action in self.keys.values_mut() {
+            *action = match *action {
+                Action::MoveLeft => Action::MoveRight,
+                Action::MoveRight => Action::MoveLeft,
+                action => action,
+            };
+        }
+        self
+    }
+
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        self.keys.get(&key).copied()
+    }
+
+    /// The keys bound to `action` as written in the config, e.g. `"+/="`.
+    pub fn keys_for(&self, action: Action) -> String {
+        let mut names: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|&(_, &bound)| bound == action)
+            .map(|(&key, _)| key_name(key))
+            .collect();
+        names.sort();
+        names.join("/")
+    }
+}
+
+/// The name of `key` understood by [`parse_key`].
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Char(c) => c.to_string(),
+        key => format!("{:?}", key),
+    }
+}
+
+/// Everything about how keys drive the game, taken from the config.
+pub struct Controls {
+    pub bindings: KeyBindings,
+    pub soft_drop: SoftDrop,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.action(KeyCode::Char('a')), Some(Action::Rotate180));
+
+        let overrides = [
+            ("rotate_180".to_string(), "s".to_string()),
+            ("toggle_debug".to_string(), "F2".to_string()),
+            ("zoom_in".to_string(), "none".to_string()),
+        ];
+        let bindings = KeyBindings::with_overrides(&overrides.into_iter().collect()).unwrap();
+        assert_eq!(bindings.action(KeyCode::Char('a')), None);
+        assert_eq!(bindings.action(KeyCode::Char('s')), Some(Action::Rotate180));
+        assert_eq!(bindings.action(KeyCode::F(2)), Some(Action::ToggleDebug));
+        assert_eq!(bindings.action(KeyCode::Up), Some(Action::Rotate));
+        assert_eq!(bindings.action(KeyCode::Char('+')), None);
+        assert_eq!(bindings.keys_for(Action::ToggleDebug), "F2");
+        assert_eq!(KeyBindings::default().keys_for(Action::ZoomIn), "+/=");
+
+        let mirrored = KeyBindings::default().mirrored();
+        assert_eq!(mirrored.action(KeyCode::Left), Some(Action::MoveRight));
+        assert_eq!(mirrored.action(KeyCode::Right), Some(Action::MoveLeft));
+        assert_eq!(mirrored.action(KeyCode::Up), Some(Action::Rotate));
+
+        let overrides = [("rotate".to_string(), "f13".to_string())];
+        assert!(KeyBindings::with_overrides(&overrides.into_iter().collect()).is_err());
+        let overrides = [("pause".to_string(), "p".to_string())];
+        assert!(KeyBindings::with_overrides(&overrides.into_iter().collect()).is_err());
+        assert_eq!(bindings.action(KeyCode::Char(' ')), Some(Action::HardDrop));
+        assert_eq!(bindings.keys_for(Action::Hold), "c");
+
+        // Quit would be left without a key
+        let overrides = [("rotate_180".to_string(), "q".to_string())];
+        assert!(KeyBindings::with_overrides(&overrides.into_iter().collect()).is_err());
+        // Moving rotate_180 frees its default key for move_left
+        let overrides = [
+            ("move_left".to_string(), "a".to_string()),
+            ("rotate_180".to_string(), "e".to_string()),
+        ];
+        assert!(KeyBindings::with_overrides(&overrides.into_iter().collect()).is_ok());
+    }
+}
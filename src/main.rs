@@ -1,12 +1,59 @@
+mod args;
+mod config;
+mod error;
 mod game;
+mod input;
+mod player;
+mod profile;
+mod save;
 mod ui;
-use std::io;
 
-fn main() -> Result<(), io::Error> {
+use std::process;
+
+use args::Args;
+use error::Error;
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("tetris_tui: {}", err);
+        if let Error::Usage(_) = err {
+            eprintln!("\n{}", usage());
+        }
+        process::exit(1);
+    }
+}
+
+fn run() -> error::Result<()> {
+    let args = Args::parse()?;
+    if args.help {
+        println!("{}", usage());
+        return Ok(());
+    }
+
     // Configure log
-    tui_logger::init_logger(log::LevelFilter::Debug).unwrap();
+    tui_logger::init_logger(log::LevelFilter::Debug)?;
     tui_logger::set_default_level(log::LevelFilter::Debug);
 
-    ui::tui::run_tui_app()?;
+    let mut config = config::Config::load()?;
+    if args.profile.is_some() {
+        config.profile = args.profile.clone();
+    }
+
+    if let Some(path) = &args.export_profile {
+        config.active_profile()?.export(path)?;
+        let exported = ui::i18n::messages().exported_profile;
+        println!("{}", ui::i18n::fill(exported, &[&path.display()]));
+        return Ok(());
+    }
+
+    ui::tui::run_tui_app(&config, &args)?;
     Ok(())
 }
+
+/// The usage text, in the language of the config if it can be read.
+fn usage() -> &'static str {
+    if let Ok(config) = config::Config::load() {
+        ui::i18n::set_locale(config.language);
+    }
+    ui::i18n::messages().usage
+}
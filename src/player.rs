@@ -0,0 +1,220 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::game::view::Stats;
+use crate::save;
+
+/// Number of high scores kept for each player.
+const HIGH_SCORES: usize = 10;
+/// Longest name a player can pick.
+const MAX_NAME_LEN: usize = 16;
+
+/// One finished game on a player's high score table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    /// When the game ended, in seconds since the Unix epoch.
+    pub ended: u64,
+}
+
+/// The best a player has done in any single game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersonalBests {
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    pub all_clears: u32,
+}
+
+/// Everything a player has played, summed up.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Totals {
+    pub sessions: u32,
+    pub games: u32,
+    pub pieces: u32,
+    pub lines: u32,
+    pub seconds: u64,
+}
+
+/// A local player on a shared machine, with records and settings kept apart
+/// from everyone else's.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Player {
+    #[serde(skip)]
+    pub name: String,
+    /// Controls profile the player last picked, used when none is given.
+    pub profile: Option<String>,
+    /// Best games first.
+    pub high_scores: Vec<HighScore>,
+    pub bests: PersonalBests,
+    /// All sessions, including the current one.
+    pub totals: Totals,
+    /// Just the current session.
+    #[serde(skip)]
+    pub session: Totals,
+}
+
+impl Player {
+    /// Checks a name can be used for a player, which also keeps it safe to
+    /// use as a directory name.
+    pub fn validate_name(name: &str) -> Result<(), String> {
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(format!(
+                "player names must be 1 to {} characters long, got {:?}",
+                MAX_NAME_LEN, name
+            ));
+        }
+        if !name.chars().all(is_name_char) {
+            return Err(format!(
+                "player names can only use letters, digits, '-' and '_', got {:?}",
+                name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Loads the player called `name`, starting a new one if they have not
+    /// played before, and counts a new session for them.
+    pub fn load(name: &str) -> Self {
+        let mut player =
+            match player_dir(name).map(|dir| fs::read_to_string(dir.join("player.json"))) {
+                Some(Ok(contents)) => serde_json::from_str(&contents).unwrap_or_else(|err| {
+                    error!(
+                        "Starting over with unreadable records for {}: {}",
+                        name, err
+                    );
+                    Self::default()
+                }),
+                _ => Self::default(),
+            };
+        player.name = name.to_string();
+        player.totals.sessions += 1;
+        player.session.sessions = 1;
+        player
+    }
+
+    /// Writes the player's records to disk.
+    pub fn save(&self) -> io::Result<()> {
+        let dir = match player_dir(&self.name) {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("player.json"), serde_json::to_string_pretty(self)?)
+    }
+
+    /// Adds a finished game to the player's records, returning its place on
+    /// the high score table if it made it.
+    pub fn record_game(&mut self, stats: &Stats, played: Duration) -> Option<usize> {
+        for totals in [&mut self.totals, &mut self.session] {
+            totals.games += 1;
+            totals.pieces += stats.pieces;
+            totals.lines += stats.lines;
+            totals.seconds += played.as_secs();
+        }
+
+        let bests = &mut self.bests;
+        bests.score = bests.score.max(stats.score);
+        bests.lines = bests.lines.max(stats.lines);
+        bests.level = bests.level.max(stats.level);
+        bests.all_clears = bests.all_clears.max(stats.all_clears);
+
+        let place = self
+            .high_scores
+            .iter()
+            .position(|high_score| stats.score > high_score.score)
+            .unwrap_or(self.high_scores.len());
+        if place >= HIGH_SCORES || stats.score == 0 {
+            return None;
+        }
+        let ended = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or_default();
+        self.high_scores.insert(
+            place,
+            HighScore {
+                score: stats.score,
+                lines: stats.lines,
+                level: stats.level,
+                ended,
+            },
+        );
+        self.high_scores.truncate(HIGH_SCORES);
+        Some(place)
+    }
+}
+
+/// Names of everyone who has played on this machine.
+pub fn known_players() -> Vec<String> {
+    let entries = match save::data_dir().map(|dir| fs::read_dir(dir.join("players"))) {
+        Some(Ok(entries)) => entries,
+        _ => return vec![],
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| Player::validate_name(name).is_ok())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Where the player's records and autosave are kept.
+pub fn player_dir(name: &str) -> Option<PathBuf> {
+    Some(save::data_dir()?.join("players").join(name))
+}
+
+pub fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_game() {
+        assert!(Player::validate_name("ana_2").is_ok());
+        assert!(Player::validate_name("").is_err());
+        assert!(Player::validate_name("../ana").is_err());
+
+        let mut player = Player::default();
+        let game = |score, lines| Stats {
+            score,
+            lines,
+            pieces: 30,
+            level: 1,
+            ..Stats::default()
+        };
+        let minute = Duration::from_secs(60);
+
+        assert_eq!(player.record_game(&game(500, 4), minute), Some(0));
+        assert_eq!(player.record_game(&game(900, 2), minute), Some(0));
+        assert_eq!(player.record_game(&game(700, 3), minute), Some(1));
+        assert_eq!(player.record_game(&game(0, 0), minute), None);
+
+        let scores: Vec<u32> = player.high_scores.iter().map(|high| high.score).collect();
+        assert_eq!(scores, [900, 700, 500]);
+        assert_eq!((player.bests.score, player.bests.lines), (900, 4));
+        assert_eq!(player.totals.games, 4);
+        assert_eq!(player.session.lines, 9);
+        assert_eq!(player.session.seconds, 240);
+
+        for _ in 0..HIGH_SCORES {
+            player.record_game(&game(1000, 0), minute);
+        }
+        assert_eq!(player.high_scores.len(), HIGH_SCORES);
+        assert_eq!(player.record_game(&game(100, 0), minute), None);
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::SoftDrop;
+use crate::error::{Error, Result};
+use crate::input::{Controls, KeyBindings};
+
+/// Profile used when the config does not pick one.
+pub const DEFAULT_PROFILE: &str = "guideline";
+
+/// A named bundle of controls, so a whole setup can be switched at once or
+/// shared as a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Soft drop speed, the config default if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soft_drop: Option<SoftDrop>,
+    /// Keys for actions, replacing their default keys. `"none"` unbinds one.
+    pub keys: BTreeMap<String, String>,
+}
+
+impl Profile {
+    /// Profiles that ship with the game.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (soft_drop, keys): (_, &[(&str, &str)]) = match name {
+            "guideline" => (Some(SoftDrop::Factor(20)), &[]),
+            "classic" => (Some(SoftDrop::Factor(2)), &[("rotate_180", "none")]),
+            "lefty" => (
+                Some(SoftDrop::Factor(20)),
+                &[
+                    ("move_left", "a"),
+                    ("move_right", "d"),
+                    ("rotate", "w"),
+                    ("rotate_180", "e"),
+                    ("soft_drop", "s"),
+                ],
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            soft_drop,
+            keys: keys
+                .iter()
+                .map(|&(action, key)| (action.to_string(), key.to_string()))
+                .collect(),
+        })
+    }
+
+    /// Reads a profile written by [`Profile::export`].
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(Error::InvalidConfig(format!(
+                    "unknown profile {:?}, expected guideline, classic, lefty, one from \
+                     [profiles] or a profile file",
+                    path
+                )))
+            }
+            Err(err) => return Err(err.into()),
+        };
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// Writes the profile to `path` as TOML, to be shared and used with
+    /// `--profile <path>` or pasted under `[profiles.<name>]`.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string(self)
+            .map_err(|err| Error::InvalidConfig(format!("cannot export profile: {}", err)))?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Applies `other` on top of this profile, keeping anything it leaves
+    /// unset.
+    pub fn merge(&mut self, other: &Profile) {
+        if other.soft_drop.is_some() {
+            self.soft_drop = other.soft_drop;
+        }
+        self.keys
+            .extend(other.keys.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    pub fn controls(&self) -> Result<Controls> {
+        let bindings = KeyBindings::with_overrides(&self.keys)
+            .map_err(|err| Error::InvalidConfig(format!("keys: {}", err)))?;
+        Ok(Controls {
+            bindings,
+            soft_drop: self.soft_drop.unwrap_or(SoftDrop::Factor(20)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_round_trip() {
+        let profile = Profile::builtin("lefty").unwrap();
+        let path = std::env::temp_dir().join("tetris_tui_test_profile.toml");
+
+        profile.export(&path).unwrap();
+        let read = Profile::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, profile);
+        assert!(Profile::read(Path::new("no/such/profile.toml")).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::{env, fs, io, path::PathBuf, sync::Mutex};
+
+use log::error;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The game state last recorded, written to disk by [`flush`].
+static LATEST: Mutex<Option<String>> = Mutex::new(None);
+/// Player whose game is saved, or `None` for a guest.
+static PLAYER: Mutex<Option<String>> = Mutex::new(None);
+
+/// Keeps saves apart for each player, `None` being the guest.
+pub fn set_player(name: Option<&str>) {
+    if let Ok(mut player) = PLAYER.lock() {
+        *player = name.map(str::to_string);
+    }
+}
+
+/// Keeps `game` as the state to write on the next [`flush`].
+pub fn record<T: Serialize>(game: &T) {
+    match serde_json::to_string(game) {
+        Ok(contents) => {
+            if let Ok(mut latest) = LATEST.lock() {
+                *latest = Some(contents);
+            }
+        }
+        Err(err) => error!("Cannot autosave the game: {}", err),
+    }
+}
+
+/// Writes the last recorded state to the save file. Only tries the lock, as
+/// this also runs from the panic hook, possibly while the lock is held.
+pub fn flush() -> io::Result<()> {
+    let latest = match LATEST.try_lock() {
+        Ok(latest) => latest,
+        Err(_) => return Ok(()),
+    };
+    let (contents, path) = match (latest.as_ref(), save_path()) {
+        (Some(contents), Some(path)) => (contents, path),
+        _ => return Ok(()),
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write to a new file and rename it over the save, so a crash while
+    // writing leaves the previous save intact
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, contents)?;
+    fs::rename(temp_path, path)
+}
+
+/// The saved game, if there is one that can still be read.
+pub fn load<T: DeserializeOwned>() -> Option<T> {
+    let contents = fs::read_to_string(save_path()?).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(game) => Some(game),
+        Err(err) => {
+            error!("Ignoring an autosave that cannot be read: {}", err);
+            None
+        }
+    }
+}
+
+/// Forgets the recorded state and deletes the save file.
+pub fn clear() {
+    if let Ok(mut latest) = LATEST.lock() {
+        *latest = None;
+    }
+    if let Some(path) = save_path() {
+        if let Err(err) = fs::remove_file(path) {
+            if err.kind() != io::ErrorKind::NotFound {
+                error!("Cannot delete the autosave: {}", err);
+            }
+        }
+    }
+}
+
+/// `$XDG_DATA_HOME/tetris_tui` or `~/.local/share/tetris_tui`.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("tetris_tui"))
+}
+
+/// `autosave.json` in the player's directory, or in [`data_dir`] for a
+/// guest.
+fn save_path() -> Option<PathBuf> {
+    let player = PLAYER.try_lock().ok()?.clone();
+    let dir = match player {
+        Some(name) => crate::player::player_dir(&name)?,
+        None => data_dir()?,
+    };
+    Some(dir.join("autosave.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tetris::{Tetris, TetrisBoard};
+    use crate::game::view::BoardView;
+
+    #[test]
+    fn test_round_trip() {
+        let mut board = TetrisBoard::new_default();
+        for _ in 0..30 {
+            board.tick();
+        }
+
+        let contents = serde_json::to_string(&board).unwrap();
+        let loaded: TetrisBoard = serde_json::from_str(&contents).unwrap();
+
+        let (loaded, board) = (loaded.view(), board.view());
+        assert_eq!(loaded.grid, board.grid);
+        assert_eq!(loaded.queue, board.queue);
+        assert_eq!(loaded.stats, board.stats);
+
+        let active_cells = |view: &BoardView| {
+            let mut cells = view.active.as_ref().unwrap().cells.clone();
+            cells.sort_by_key(|cell| (cell.0, cell.1));
+            cells
+        };
+        assert_eq!(active_cells(&loaded), active_cells(&board));
+    }
+
+    #[test]
+    fn test_autosave() {
+        let dir = env::temp_dir().join(format!("tetris_tui_test_save_{}", std::process::id()));
+        env::set_var("XDG_DATA_HOME", &dir);
+        set_player(None);
+        let path = dir.join("tetris_tui/autosave.json");
+
+        let mut board = TetrisBoard::new_default();
+        board.tick();
+        record(&board);
+        flush().unwrap();
+        assert!(path.exists());
+
+        let loaded: TetrisBoard = load().unwrap();
+        assert_eq!(loaded.view().stats, board.view().stats);
+        assert_eq!(loaded.view().queue, board.view().queue);
+
+        // Nothing is left to write back once the save is cleared
+        clear();
+        assert!(!path.exists());
+        flush().unwrap();
+        assert!(load::<TetrisBoard>().is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
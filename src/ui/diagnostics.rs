@@ -0,0 +1,97 @@
+use std::{collections::VecDeque, time::Duration};
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::Spans,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::i18n;
+
+/// Number of frames the average and worst frame time are taken over.
+const SAMPLES: usize = 60;
+
+/// Loop timings shown by the debug overlay, toggled with F3.
+#[derive(Default)]
+pub struct Diagnostics {
+    pub visible: bool,
+    frame_times: VecDeque<Duration>,
+    render_time: Duration,
+    tick_drift: Duration,
+    pending_events: usize,
+}
+
+impl Diagnostics {
+    /// Time from the start of one loop iteration to the start of the next.
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == SAMPLES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Time spent building and flushing a frame to the terminal.
+    pub fn record_render(&mut self, render_time: Duration) {
+        self.render_time = render_time;
+    }
+
+    /// How late a tick fired compared to when it was due.
+    pub fn record_tick_drift(&mut self, drift: Duration) {
+        self.tick_drift = drift;
+    }
+
+    /// Events that were waiting to be handled in one iteration.
+    pub fn record_events(&mut self, pending_events: usize) {
+        self.pending_events = pending_events;
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>) {
+        if !self.visible {
+            return;
+        }
+
+        let average = if self.frame_times.is_empty() {
+            Duration::ZERO
+        } else {
+            self.frame_times.iter().sum::<Duration>() / self.frame_times.len() as u32
+        };
+        let worst = self.frame_times.iter().max().copied().unwrap_or_default();
+
+        let messages = i18n::messages();
+        let timing = |label, duration| format!("{:<7} {:>7.2} ms", label, as_millis(duration));
+        let lines = vec![
+            Spans::from(timing(messages.debug_frame, average)),
+            Spans::from(timing(messages.debug_worst, worst)),
+            Spans::from(timing(messages.debug_render, self.render_time)),
+            Spans::from(timing(messages.debug_drift, self.tick_drift)),
+            Spans::from(format!(
+                "{:<7} {:>7}",
+                messages.debug_events, self.pending_events
+            )),
+        ];
+
+        let size = f.size();
+        let width = 23.min(size.width);
+        let height = (lines.len() as u16 + 2).min(size.height);
+        let area = Rect {
+            x: size.width - width,
+            y: 0,
+            width,
+            height,
+        };
+
+        let overlay = Paragraph::new(lines)
+            .style(Style::default().fg(Color::Yellow).bg(Color::Black))
+            .block(Block::default().title(messages.debug).borders(Borders::ALL));
+
+        f.render_widget(Clear, area);
+        f.render_widget(overlay, area);
+    }
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,166 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+};
+
+use log::{debug, warn};
+
+use crate::error::Result;
+use crate::game::view::BoardView;
+use crate::input::Action;
+
+/// Board states waiting to be written to a bot that has not read the last
+/// ones yet. Any more are dropped rather than holding up the game.
+const QUEUED_STATES: usize = 4;
+
+/// A bot running in another process. Each new board state is written to its
+/// stdin as one line of JSON, and it answers with move names such as
+/// `move_left` or `rotate`, one per line, on its stdout.
+pub struct ExternalBot {
+    child: Child,
+    states: SyncSender<String>,
+    actions: Receiver<Action>,
+    last_sent: Option<BoardView>,
+}
+
+impl ExternalBot {
+    /// Starts `command` through the system shell.
+    pub fn spawn(command: &str) -> Result<Self> {
+        let mut child = shell(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+
+        // A bot that stops reading would otherwise block the game loop on a
+        // full pipe
+        let (states, queue) = mpsc::sync_channel::<String>(QUEUED_STATES);
+        thread::spawn(move || {
+            for line in queue {
+                if let Err(err) = stdin.write_all(line.as_bytes()).and_then(|_| stdin.flush()) {
+                    debug!("Stopped writing to the bot: {}", err);
+                    break;
+                }
+            }
+        });
+        let (sender, actions) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                match line.trim().parse::<Action>() {
+                    Ok(action) if action.is_move() => {
+                        if sender.send(action).is_err() {
+                            break;
+                        }
+                    }
+                    _ => warn!("Ignoring bot command {:?}", line),
+                }
+            }
+        });
+        // Anything the bot prints to stderr goes to the log, where it does
+        // not mess up the screen
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+                debug!("bot: {}", line);
+            }
+        });
+
+        Ok(Self {
+            child,
+            states,
+            actions,
+            last_sent: None,
+        })
+    }
+
+    /// Sends `view` to the bot unless it is the same as the last one sent.
+    /// While the bot is behind on reading, the view is dropped and sent again
+    /// on the next call if the board has not changed by then.
+    pub fn send(&mut self, view: BoardView) -> io::Result<()> {
+        if self.last_sent.as_ref() == Some(&view) {
+            return Ok(());
+        }
+
+        let mut line = serde_json::to_string(&view)?;
+        line.push('\n');
+        match self.states.try_send(line) {
+            Ok(()) => self.last_sent = Some(view),
+            Err(TrySendError::Full(_)) => {}
+            Err(TrySendError::Disconnected(_)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "the bot closed its stdin",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// The next move the bot has asked for, if any has arrived.
+    pub fn next_action(&mut self) -> Option<Action> {
+        self.actions.try_recv().ok()
+    }
+}
+
+impl Drop for ExternalBot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::game::tetris::{Direction, Tetris, TetrisBoard};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_external_bot() {
+        let command = "read view; echo rotate; echo quit; echo teleport; echo move_left";
+        let mut bot = ExternalBot::spawn(command).unwrap();
+
+        bot.send(TetrisBoard::new_default().view()).unwrap();
+
+        let timeout = Duration::from_secs(5);
+        assert_eq!(bot.actions.recv_timeout(timeout), Ok(Action::Rotate));
+        assert_eq!(bot.actions.recv_timeout(timeout), Ok(Action::MoveLeft));
+    }
+    #[test]
+    fn test_bot_that_never_reads() {
+        let mut bot = ExternalBot::spawn("exec sleep 30").unwrap();
+        let mut board = TetrisBoard::new_default();
+
+        // Far more than fits in a pipe, all of it different
+        let started = Instant::now();
+        for turn in 0..2000 {
+            let direction = if turn % 2 == 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            board.shift(direction);
+            bot.send(board.view()).unwrap();
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,407 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use serde::Deserialize;
+
+use crate::game::bot::Difficulty;
+use crate::game::objective::Objective;
+use crate::game::piece_set::PieceSet;
+use crate::game::royale::Targeting;
+
+/// Language of the text shown in game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    En,
+    Es,
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+/// Switches the language returned by [`messages`].
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The text for the current locale.
+pub fn messages() -> &'static Messages {
+    if LOCALE.load(Ordering::Relaxed) == Locale::Es as u8 {
+        &ES
+    } else {
+        &EN
+    }
+}
+
+/// Every piece of text the game shows. `{}` marks where values go, filled in
+/// order by [`fill`].
+pub struct Messages {
+    pub title: &'static str,
+    pub board: &'static str,
+    pub cpu: &'static str,
+    pub logs: &'static str,
+    pub stats: &'static str,
+    pub next: &'static str,
+    pub hold: &'static str,
+    pub level: &'static str,
+    pub goal: &'static str,
+    pub score: &'static str,
+    pub lines: &'static str,
+    pub pieces: &'static str,
+    pub all_clears: &'static str,
+    pub objective: &'static str,
+    pub no_objective: &'static str,
+    pub objectives_completed: &'static str,
+    pub resume_prompt: &'static str,
+    pub quit_prompt: &'static str,
+    pub game_started: &'static str,
+    pub game_over: &'static str,
+    pub cpu_beaten: &'static str,
+    pub config_reloaded: &'static str,
+    pub all_clear: &'static str,
+    pub all_clear_banner: &'static str,
+    pub objective_complete: &'static str,
+    pub objective_complete_banner: &'static str,
+    pub new_objective: &'static str,
+    pub new_objective_banner: &'static str,
+    pub clear_lines_with: &'static str,
+    pub clear_lines: &'static str,
+    pub t_spin: &'static str,
+    pub clear_board: &'static str,
+    pub randomizer_stats: &'static str,
+    pub piece: &'static str,
+    pub share: &'static str,
+    pub drought: &'static str,
+    pub repeat_gaps: &'static str,
+    pub who_is_playing: &'static str,
+    pub known_players: &'static str,
+    pub no_players: &'static str,
+    pub playing_as: &'static str,
+    pub new_high_score: &'static str,
+    pub session_stats: &'static str,
+    pub tutorial: &'static str,
+    pub tutorial_move: &'static str,
+    pub tutorial_rotate: &'static str,
+    pub tutorial_rotate_180: &'static str,
+    pub tutorial_soft_drop: &'static str,
+    pub tutorial_hard_drop: &'static str,
+    pub tutorial_hold: &'static str,
+    pub tutorial_clear_lines: &'static str,
+    pub tutorial_t_spin: &'static str,
+    pub tutorial_retry: &'static str,
+    pub tutorial_done: &'static str,
+    pub rewound: &'static str,
+    pub rewind_refused: &'static str,
+    pub window_title: &'static str,
+    pub window_score: &'static str,
+    pub paused: &'static str,
+    pub mode_single: &'static str,
+    pub mode_cpu: &'static str,
+    pub mode_bot: &'static str,
+    pub mode_royale: &'static str,
+    pub royale_won: &'static str,
+    pub royale_out: &'static str,
+    pub bot_out: &'static str,
+    pub boards_left: &'static str,
+    pub targeting: &'static str,
+    pub incoming: &'static str,
+    pub target_random: &'static str,
+    pub target_attackers: &'static str,
+    pub target_leader: &'static str,
+    pub new_personal_best: &'static str,
+    /// Key that answers yes to a question, besides Enter.
+    pub yes: char,
+    /// Key that answers no to a question, besides Esc.
+    pub no: char,
+    pub usage: &'static str,
+    pub exported_profile: &'static str,
+    pub screen_too_small: &'static str,
+    pub easy: &'static str,
+    pub normal: &'static str,
+    pub hard: &'static str,
+    pub debug: &'static str,
+    pub debug_frame: &'static str,
+    pub debug_worst: &'static str,
+    pub debug_render: &'static str,
+    pub debug_drift: &'static str,
+    pub debug_events: &'static str,
+}
+
+impl Messages {
+    pub fn objective(&self, objective: Objective) -> String {
+        match objective {
+            Objective::Clear {
+                lines,
+                shape: Some(shape),
+            } => fill(
+                self.clear_lines_with,
+                &[&lines, &PieceSet::standard().name(shape).to_string()],
+            ),
+            Objective::Clear { lines, shape: None } => fill(self.clear_lines, &[&lines]),
+            Objective::TSpin => self.t_spin.to_string(),
+            Objective::AllClear => self.clear_board.to_string(),
+        }
+    }
+
+    pub fn targeting(&self, targeting: Targeting) -> &'static str {
+        match targeting {
+            Targeting::Random => self.target_random,
+            Targeting::Attackers => self.target_attackers,
+            Targeting::Leader => self.target_leader,
+        }
+    }
+
+    pub fn difficulty(&self, difficulty: Difficulty) -> &'static str {
+        match difficulty {
+            Difficulty::Easy => self.easy,
+            Difficulty::Normal => self.normal,
+            Difficulty::Hard => self.hard,
+        }
+    }
+}
+
+/// Replaces each `{}` in `template` with the next of `args`.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+static EN: Messages = Messages {
+    title: "Tetris TUI",
+    board: "Game Board",
+    cpu: "CPU ({})",
+    logs: "Logs",
+    stats: "Stats",
+    next: "Next:",
+    hold: "Hold: ",
+    level: "Level:  {}",
+    goal: "Goal:   {}",
+    score: "Score:  {}",
+    lines: "Lines:  {}",
+    pieces: "Pieces: {}",
+    all_clears: "All clears: {}",
+    objective: "Objective",
+    no_objective: "Keep playing...",
+    objectives_completed: "Completed: {}",
+    resume_prompt: "Resume your last game? (y/n)",
+    quit_prompt: "Quit this game? (y/n)",
+    game_started: "Game started!",
+    game_over: "Game over! Press n for a new game or q to quit",
+    cpu_beaten: "You beat the CPU! Press n for a new game or q to quit",
+    config_reloaded: "Config reloaded",
+    all_clear: "All clear!",
+    all_clear_banner: "ALL CLEAR",
+    objective_complete: "Objective complete: {}",
+    objective_complete_banner: "OBJECTIVE COMPLETE",
+    new_objective: "New objective: {}",
+    new_objective_banner: "NEW OBJECTIVE",
+    clear_lines_with: "Clear {} lines with {}",
+    clear_lines: "Clear {} lines at once",
+    t_spin: "Perform a T-spin",
+    clear_board: "Clear the whole board",
+    randomizer_stats: "Randomizer: {} ({} pieces)",
+    piece: "Piece",
+    share: "Share",
+    drought: "Drought",
+    repeat_gaps: "Pieces until a repeat",
+    who_is_playing: "Who is playing? Type a name, or press Enter to play as a guest",
+    known_players: "Players: {}. A new name creates a new player",
+    no_players: "No players yet. A name creates a new player",
+    playing_as: "Playing as {}, best score {}",
+    new_high_score: "New high score! #{} for {}",
+    session_stats: "This session: {} games, {} lines, {} min",
+    tutorial: "Tutorial {}/{}",
+    tutorial_move: "Move the piece left with {} and right with {}",
+    tutorial_rotate: "Now press {} to rotate the piece",
+    tutorial_rotate_180: "Press {} to turn it all the way around at once",
+    tutorial_soft_drop: "Hold {} to soft drop the piece down to the floor",
+    tutorial_hard_drop: "Press {} to hard drop the piece, locking it on the floor at once",
+    tutorial_hold: "Press {} to put the piece on hold and play the next one",
+    tutorial_clear_lines: "Drop the I piece into the gap to clear four lines",
+    tutorial_t_spin: "T-spin: turn the T with {} to point right, drop it beside the \
+                      slot with {}, then rotate it in before it locks",
+    tutorial_retry: "TRY AGAIN",
+    tutorial_done: "Tutorial complete! Press any key to leave",
+    rewound: "Rewound {} seconds for {} points",
+    rewind_refused: "Rewinding costs {} points, score more first",
+    window_title: "Tetris TUI | {} | {}",
+    window_score: "Score {}",
+    paused: "Paused",
+    mode_single: "Single player",
+    mode_cpu: "vs CPU",
+    mode_bot: "Bot",
+    mode_royale: "Battle royale",
+    royale_won: "Last board standing! Press n for a new game or q to quit",
+    royale_out: "Knocked out in place {} of {}. Press n for a new game or q to quit",
+    bot_out: "Bot {} is out, {} boards left",
+    boards_left: "Boards: {}/{}",
+    targeting: "Attack: {}",
+    incoming: "Incoming: {}",
+    target_random: "random",
+    target_attackers: "attackers",
+    target_leader: "leader",
+    new_personal_best: "New personal best: {} points",
+    yes: 'y',
+    no: 'n',
+    usage: "\
+Usage: tetris_tui [OPTIONS]
+
+Options:
+  --cpu <easy|normal|hard>  Play against a CPU opponent
+  --royale <bots>           Play a battle royale against 1 to 24 bots
+  --bot <command>           Let an external program play, see the README
+  --profile <name|file>     Use a controls profile: guideline, classic, lefty,
+                            one from the config or an exported file
+  --export-profile <file>   Write the active controls profile to a file
+  --player <name>           Play as a local player, keeping their scores and
+                            settings apart from everyone else's
+  --tutorial                Learn the controls step by step
+  -h, --help                Print this help",
+    exported_profile: "Exported profile to {}",
+    screen_too_small: "The screen is too small to show the board",
+    easy: "easy",
+    normal: "normal",
+    hard: "hard",
+    debug: "Debug (F3)",
+    debug_frame: "frame",
+    debug_worst: "worst",
+    debug_render: "render",
+    debug_drift: "drift",
+    debug_events: "events",
+};
+
+static ES: Messages = Messages {
+    title: "Tetris TUI",
+    board: "Tablero",
+    cpu: "CPU ({})",
+    logs: "Registro",
+    stats: "Estadísticas",
+    next: "Siguiente:",
+    hold: "Reserva: ",
+    level: "Nivel:  {}",
+    goal: "Meta:   {}",
+    score: "Puntos: {}",
+    lines: "Líneas: {}",
+    pieces: "Piezas: {}",
+    all_clears: "Tableros limpios: {}",
+    objective: "Objetivo",
+    no_objective: "Sigue jugando...",
+    objectives_completed: "Completados: {}",
+    resume_prompt: "¿Continuar la última partida? (s/n)",
+    quit_prompt: "¿Salir de esta partida? (s/n)",
+    game_started: "¡Empieza la partida!",
+    game_over: "¡Fin de la partida! Pulsa n para jugar otra o q para salir",
+    cpu_beaten: "¡Has ganado a la CPU! Pulsa n para jugar otra o q para salir",
+    config_reloaded: "Configuración recargada",
+    all_clear: "¡Tablero limpio!",
+    all_clear_banner: "TABLERO LIMPIO",
+    objective_complete: "Objetivo cumplido: {}",
+    objective_complete_banner: "OBJETIVO CUMPLIDO",
+    new_objective: "Nuevo objetivo: {}",
+    new_objective_banner: "NUEVO OBJETIVO",
+    clear_lines_with: "Haz {} líneas con {}",
+    clear_lines: "Haz {} líneas de una vez",
+    t_spin: "Haz un T-spin",
+    clear_board: "Limpia todo el tablero",
+    randomizer_stats: "Generador: {} ({} piezas)",
+    piece: "Pieza",
+    share: "Parte",
+    drought: "Sequía",
+    repeat_gaps: "Piezas hasta repetir",
+    who_is_playing: "¿Quién juega? Escribe un nombre o pulsa Enter para jugar como invitado",
+    known_players: "Jugadores: {}. Un nombre nuevo crea un jugador nuevo",
+    no_players: "Aún no hay jugadores. Un nombre crea un jugador nuevo",
+    playing_as: "Jugando como {}, mejor puntuación {}",
+    new_high_score: "¡Nueva mejor puntuación! #{} para {}",
+    session_stats: "Esta sesión: {} partidas, {} líneas, {} min",
+    tutorial: "Tutorial {}/{}",
+    tutorial_move: "Mueve la pieza a la izquierda con {} y a la derecha con {}",
+    tutorial_rotate: "Ahora pulsa {} para girar la pieza",
+    tutorial_rotate_180: "Pulsa {} para darle media vuelta de una vez",
+    tutorial_soft_drop: "Mantén {} para bajar la pieza hasta el suelo",
+    tutorial_hard_drop: "Pulsa {} para dejar caer la pieza y fijarla en el suelo de golpe",
+    tutorial_hold: "Pulsa {} para guardar la pieza en reserva y jugar la siguiente",
+    tutorial_clear_lines: "Mete la pieza I en el hueco para hacer cuatro líneas",
+    tutorial_t_spin: "T-spin: gira la T con {} hacia la derecha, bájala junto al \
+                      hueco con {} y gírala dentro antes de que se fije",
+    tutorial_retry: "OTRA VEZ",
+    tutorial_done: "¡Tutorial completado! Pulsa una tecla para salir",
+    rewound: "Retrocediste {} segundos por {} puntos",
+    rewind_refused: "Retroceder cuesta {} puntos, consigue más primero",
+    window_title: "Tetris TUI | {} | {}",
+    window_score: "Puntos {}",
+    paused: "En pausa",
+    mode_single: "Un jugador",
+    mode_cpu: "contra la CPU",
+    mode_bot: "Bot",
+    mode_royale: "Batalla real",
+    royale_won: "¡Último tablero en pie! Pulsa n para jugar otra o q para salir",
+    royale_out: "Eliminado en el puesto {} de {}. Pulsa n para jugar otra o q para salir",
+    bot_out: "El bot {} queda fuera, quedan {} tableros",
+    boards_left: "Tableros: {}/{}",
+    targeting: "Ataque: {}",
+    incoming: "Entrante: {}",
+    target_random: "al azar",
+    target_attackers: "atacantes",
+    target_leader: "líder",
+    new_personal_best: "Nueva mejor marca personal: {} puntos",
+    yes: 's',
+    no: 'n',
+    usage: "\
+Uso: tetris_tui [OPCIONES]
+
+Opciones:
+  --cpu <easy|normal|hard>  Juega contra la CPU
+  --royale <bots>           Juega una batalla real contra 1 a 24 bots
+  --bot <comando>           Deja jugar a un programa externo, ver el README
+  --profile <nombre|archivo>
+                            Usa un perfil de controles: guideline, classic,
+                            lefty, uno de la configuración o un archivo
+                            exportado
+  --export-profile <archivo>
+                            Guarda el perfil de controles activo en un archivo
+  --player <nombre>         Juega como un jugador local, con sus puntuaciones
+                            y ajustes aparte de los de los demás
+  --tutorial                Aprende los controles paso a paso
+  -h, --help                Muestra esta ayuda",
+    exported_profile: "Perfil exportado a {}",
+    screen_too_small: "La pantalla es demasiado pequeña para el tablero",
+    easy: "fácil",
+    normal: "normal",
+    hard: "difícil",
+    debug: "Depuración (F3)",
+    debug_frame: "cuadro",
+    debug_worst: "peor",
+    debug_render: "dibujo",
+    debug_drift: "desvío",
+    debug_events: "eventos",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::shape::Shape;
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("Score: {}", &[&42]), "Score: 42");
+        assert_eq!(fill("{} and {}", &[&1]), "1 and ");
+        assert_eq!(fill("no values", &[&1]), "no values");
+
+        let objective = Objective::Clear {
+            lines: 2,
+            shape: Some(Shape::I),
+        };
+        assert_eq!(EN.objective(objective), "Clear 2 lines with I");
+        assert_eq!(ES.objective(objective), "Haz 2 líneas con I");
+    }
+}
@@ -1 +1,9 @@
+pub mod diagnostics;
+pub mod external;
+pub mod i18n;
+pub mod notify;
+pub mod randomizer_stats;
+pub mod terminal;
+pub mod thumbnail;
 pub mod tui;
+pub mod tutorial;
@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns desktop notifications on or off, off being the default.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Shows a desktop notification if they are turned on. Builds without the
+/// `notifications` feature have nowhere to show it and only log it.
+pub fn send(summary: &str, body: &str) {
+    if ENABLED.load(Ordering::Relaxed) {
+        show(summary, body);
+    }
+}
+
+#[cfg(feature = "notifications")]
+fn show(summary: &str, body: &str) {
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("tetris_tui")
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        log::error!("Cannot show a notification: {}", err);
+    }
+}
+
+#[cfg(not(feature = "notifications"))]
+fn show(summary: &str, body: &str) {
+    log::debug!("Notification without a notifier: {}: {}", summary, body);
+}
@@ -0,0 +1,104 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::Spans,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::game::piece_set::PieceSet;
+use crate::game::randomizer::{Randomizer, RandomizerStats, MAX_INTERVAL};
+
+use super::i18n::{self, fill};
+
+/// Pieces dealt for each look at a randomizer.
+const PIECES: u32 = 10_000;
+/// Width of the longest bar in the interval histogram.
+const BAR_WIDTH: usize = 20;
+
+/// How fairly the configured randomizer deals pieces, toggled with F5.
+#[derive(Default)]
+pub struct RandomizerScreen {
+    /// The randomizer, the names of the pieces it dealt and how it dealt
+    /// them.
+    shown: Option<(Randomizer, Vec<String>, RandomizerStats)>,
+}
+
+impl RandomizerScreen {
+    /// Closes the screen, or opens it with a fresh run of `randomizer`
+    /// dealing from `pieces`.
+    pub fn toggle(&mut self, randomizer: Randomizer, pieces: &PieceSet) {
+        self.shown = match self.shown {
+            Some(_) => None,
+            None => {
+                let shapes = pieces.pieces.len();
+                let stats =
+                    RandomizerStats::simulate(&mut randomizer.generator(shapes), shapes, PIECES);
+                let names = pieces.pieces.iter().map(|def| def.name.clone()).collect();
+                Some((randomizer, names, stats))
+            }
+        };
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>) {
+        let (randomizer, names, stats) = match &self.shown {
+            Some(shown) => shown,
+            None => return,
+        };
+        let messages = i18n::messages();
+        let percent = |count: u32| 100.0 * count as f64 / stats.pieces.max(1) as f64;
+
+        let mut lines = vec![Spans::from(format!(
+            "{:<7}{:>7}{:>9}",
+            messages.piece, messages.share, messages.drought
+        ))];
+        for (index, name) in names.iter().enumerate() {
+            lines.push(Spans::from(format!(
+                "{:<7}{:>6.1}%{:>9}",
+                name,
+                percent(stats.counts[index]),
+                stats.droughts[index]
+            )));
+        }
+
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(messages.repeat_gaps));
+        let most = stats.intervals.iter().max().copied().unwrap_or(0).max(1);
+        for (interval, &count) in stats.intervals.iter().enumerate().skip(1) {
+            let label = match interval {
+                MAX_INTERVAL => format!("{}+", interval),
+                _ => interval.to_string(),
+            };
+            let bar = "█".repeat(count as usize * BAR_WIDTH / most as usize);
+            lines.push(Spans::from(format!(
+                "{:>3} {:<width$} {:>5.1}%",
+                label,
+                bar,
+                percent(count),
+                width = BAR_WIDTH
+            )));
+        }
+
+        let size = f.size();
+        let width = 34.min(size.width);
+        let height = (lines.len() as u16 + 2).min(size.height);
+        let area = Rect {
+            x: size.width.saturating_sub(width) / 2,
+            y: size.height.saturating_sub(height) / 2,
+            width,
+            height,
+        };
+
+        let title = fill(
+            messages.randomizer_stats,
+            &[&randomizer.name(), &stats.pieces],
+        );
+        let overlay = Paragraph::new(lines)
+            .style(Style::default().fg(Color::Cyan).bg(Color::Black))
+            .block(Block::default().title(title).borders(Borders::ALL));
+
+        f.render_widget(Clear, area);
+        f.render_widget(overlay, area);
+    }
+}
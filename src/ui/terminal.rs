@@ -0,0 +1,123 @@
+use std::{
+    io::{self, BufWriter, Stdout},
+    ops::{Deref, DerefMut},
+    panic,
+};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    style::Print,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
+};
+use log::error;
+use tui::{backend::CrosstermBackend, Terminal};
+
+use crate::error::Result;
+use crate::save;
+
+/// Bytes buffered before writing to the terminal, enough for a whole frame so
+/// each redraw goes out in one write instead of many small ones.
+const WRITE_BUFFER: usize = 64 * 1024;
+
+/// xterm sequences saving the window title on the terminal's title stack and
+/// putting it back. Terminals without the stack ignore them.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// Owns the terminal while the game runs and puts it back into its normal
+/// state when dropped, including when unwinding from a panic.
+pub struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<BufWriter<Stdout>>>,
+}
+
+impl TerminalGuard {
+    pub fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        // There is no guard to drop yet, so undo whatever got done by hand
+        Self::set_up().inspect_err(|_| {
+            let _ = restore_terminal();
+        })
+    }
+
+    fn set_up() -> Result<Self> {
+        let mut stdout = io::stdout();
+        execute!(
+            stdout,
+            Print(PUSH_TITLE),
+            EnterAlternateScreen,
+            EnableMouseCapture
+        )?;
+        let backend = CrosstermBackend::new(BufWriter::with_capacity(WRITE_BUFFER, stdout));
+        let mut terminal = Terminal::new(backend)?;
+
+        terminal.clear()?;
+        terminal.hide_cursor()?;
+
+        Ok(Self { terminal })
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Terminal<CrosstermBackend<BufWriter<Stdout>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Nothing sensible can be done with a failure while tearing down.
+        let _ = restore_terminal();
+    }
+}
+
+/// The title of the terminal window, only written out when it changes.
+#[derive(Debug, Default)]
+pub struct WindowTitle {
+    shown: String,
+}
+
+impl WindowTitle {
+    pub fn set(&mut self, title: String) {
+        if title == self.shown {
+            return;
+        }
+        if let Err(err) = execute!(io::stdout(), SetTitle(&title)) {
+            error!("Cannot set the window title: {}", err);
+        }
+        self.shown = title;
+    }
+}
+
+/// Restores the terminal before the default panic message is printed, so the
+/// message is readable and the shell is not left in raw mode. The game in
+/// progress is autosaved first so it can be resumed.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = save::flush();
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        crossterm::cursor::Show,
+        Print(POP_TITLE)
+    )
+}
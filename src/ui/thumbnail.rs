@@ -0,0 +1,99 @@
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::game::shape::Cell;
+use crate::game::view::BoardView;
+
+/// A board drawn small, two rows to a line with half block characters, for
+/// showing many opponents at once.
+pub struct Thumbnail {
+    pub view: BoardView,
+    pub title: String,
+    /// Border colour, to mark boards being attacked or attacking.
+    pub border: Color,
+    /// Whether the board is out of the game, which greys it out.
+    pub out: bool,
+}
+
+impl Thumbnail {
+    fn size(&self) -> (u16, u16) {
+        (
+            self.view.width as u16 + 2,
+            (self.view.height as u16).div_ceil(2) + 2,
+        )
+    }
+
+    fn color(&self, cell: Cell) -> Color {
+        match self.view.get(cell) {
+            Some(_) if self.out => Color::DarkGray,
+            Some(shape) => {
+                let (red, green, blue) = self.view.pieces.color(shape);
+                Color::Rgb(red, green, blue)
+            }
+            None => Color::Black,
+        }
+    }
+
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, mirror: bool, area: Rect) {
+        let (width, height) = (self.view.width, self.view.height);
+        let lines: Vec<Spans> = (0..height)
+            .step_by(2)
+            .map(|y| {
+                let spans: Vec<Span> = (0..width)
+                    .map(|x| {
+                        let x = if mirror { width - 1 - x } else { x };
+                        let bottom = if y + 1 < height {
+                            self.color(Cell(x, y + 1))
+                        } else {
+                            Color::Reset
+                        };
+                        Span::styled("▀", Style::default().fg(self.color(Cell(x, y))).bg(bottom))
+                    })
+                    .collect();
+                Spans::from(spans)
+            })
+            .collect();
+
+        let block = Block::default()
+            .title(self.title.as_str())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.border));
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+/// Lays `thumbnails` out in a grid in `area`, as many as fit, returning how
+/// many were drawn.
+pub fn draw_thumbnails<B: Backend>(
+    f: &mut Frame<B>,
+    thumbnails: &[Thumbnail],
+    mirror: bool,
+    area: Rect,
+) -> usize {
+    let (width, height) = match thumbnails.first() {
+        Some(thumbnail) => thumbnail.size(),
+        None => return 0,
+    };
+    let columns = area.width / width;
+    let rows = area.height / height;
+    let left = area.x + (area.width - columns * width) / 2;
+
+    let fits = (columns * rows) as usize;
+    for (index, thumbnail) in thumbnails.iter().take(fits).enumerate() {
+        let (column, row) = (index as u16 % columns, index as u16 / columns);
+        let cell = Rect {
+            x: left + column * width,
+            y: area.y + row * height,
+            width,
+            height,
+        };
+        thumbnail.draw(f, mirror, cell);
+    }
+    fits.min(thumbnails.len())
+}
@@ -1,51 +1,348 @@
-use std::{
-    io,
-    time::{Duration, Instant},
+use std::time::{Duration, Instant};
+
+use crate::args::Args;
+use crate::config::{Config, ConfigWatcher, RenderMode, Zoom};
+use crate::error::Result;
+use crate::game::bot::{Bot, Difficulty, Move};
+use crate::game::event::GameEvent;
+use crate::game::level;
+use crate::game::objective::Objectives;
+use crate::game::rewind::{Rewind, REWIND_COST};
+use crate::game::royale::{Royale, Standing};
+use crate::game::tetris::{Direction as Shift, Tetris};
+use crate::game::{
+    shape::Shape,
+    tetris::TetrisBoard,
+    view::{BoardView, Stats},
 };
+use crate::input::{Action, Controls};
+use crate::player::{self, Player};
+use crate::save;
 
-use crate::game::tetris::Tetris;
-use crate::game::{shape::Shape, tetris::TetrisBoard};
+use super::diagnostics::Diagnostics;
+use super::external::ExternalBot;
+use super::i18n::{self, fill};
+use super::notify;
+use super::randomizer_stats::RandomizerScreen;
+use super::terminal::{self, TerminalGuard, WindowTitle};
+use super::thumbnail::{self, Thumbnail};
+use super::tutorial::{Progress, Tutorial};
 
 use tui::{
     backend::Backend,
-    backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    text::Span,
-    widgets::{Block, BorderType, Borders, Paragraph},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
     Frame, Terminal,
 };
 
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event, KeyCode};
 
-use log::{error, info};
+use log::{error, info, warn};
+use rand::seq::SliceRandom;
+use serde::Serialize;
 use tui_logger::TuiLoggerWidget;
 
 #[derive(PartialEq, Eq)]
 enum GameState {
     Quit,
     Failed,
+    Won,
+}
+
+/// A short message flashed over the board.
+struct Banner {
+    text: &'static str,
+    until: Instant,
+}
+
+/// How long a banner stays on screen.
+const BANNER_DURATION: Duration = Duration::from_secs(2);
+
+/// How often the game in progress is written to the autosave file.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest wait for input while an external bot plays, as its moves do not
+/// wake up the terminal poll.
+const EXTERNAL_BOT_POLL: Duration = Duration::from_millis(10);
+
+/// Time between rows falling in the tutorial, slow enough to take in each
+/// step.
+const TUTORIAL_GRAVITY: Duration = Duration::from_secs(1);
+
+/// How long soft drop stays on after a Down key press. Terminals only report
+/// presses, so holding the key is seen as a stream of key repeats.
+const SOFT_DROP_HOLD: Duration = Duration::from_millis(150);
+
+/// Rows the side panel takes in a battle royale, borders included.
+const ROYALE_PANEL_HEIGHT: u16 = 18;
+
+/// Rows of log shown under the bots in a battle royale.
+const ROYALE_LOG_HEIGHT: u16 = 8;
+
+/// A CPU-controlled board played alongside the player's.
+struct Opponent {
+    board: TetrisBoard,
+    bot: Bot,
+    last_move: Instant,
+    /// Whether the board has been counted as knocked out.
+    out: bool,
+}
+
+impl Opponent {
+    fn new(board: TetrisBoard, difficulty: Difficulty) -> Self {
+        Self {
+            board,
+            bot: Bot::new(difficulty),
+            last_move: Instant::now(),
+            out: false,
+        }
+    }
+
+    fn play_move(&mut self) {
+        match self.bot.next_move(&self.board) {
+            Some(Move::Rotate) => self.board.rotate(),
+            Some(Move::Shift(direction)) => self.board.shift(direction),
+            None => {}
+        }
+        self.last_move = Instant::now();
+    }
+}
+
+/// The CPU boards played alongside the player's, and in a battle royale who
+/// their garbage goes to.
+#[derive(Default)]
+struct Opponents {
+    boards: Vec<Opponent>,
+    royale: Option<Royale>,
+}
+
+impl Opponents {
+    /// Whether every opponent is out, winning the game for the player.
+    fn all_out(&self) -> bool {
+        !self.boards.is_empty() && self.boards.iter().all(|opponent| !opponent.board.alive())
+    }
+}
+
+/// How every board is doing, the player's first.
+fn standings<T: Tetris>(tetris: &T, boards: &[Opponent]) -> Vec<Standing> {
+    let mut standings = vec![Standing {
+        alive: tetris.alive(),
+        score: tetris.view().stats.score,
+    }];
+    standings.extend(boards.iter().map(|opponent| Standing {
+        alive: opponent.board.alive(),
+        score: opponent.board.view().stats.score,
+    }));
+    standings
+}
+
+/// How the board is laid out on screen, kept across games.
+struct BoardStyle {
+    zoom: Zoom,
+    aspect_correct: bool,
+    render: RenderMode,
+    /// Show column heights and holes under the board.
+    analysis: bool,
+    /// Draw boards flipped left to right.
+    mirror: bool,
+}
+
+impl BoardStyle {
+    fn new(config: &Config) -> Self {
+        Self {
+            zoom: config.zoom,
+            aspect_correct: config.aspect_correct,
+            render: config.render,
+            analysis: config.analysis,
+            mirror: config.mirror,
+        }
+    }
+
+    /// Takes up the settings changed between `old` and `new`, keeping the
+    /// zoom and analysis the player picked in game unless their config value
+    /// changed.
+    fn update(&mut self, old: &Config, new: &Config) {
+        if new.zoom != old.zoom {
+            self.zoom = new.zoom;
+        }
+        if new.analysis != old.analysis {
+            self.analysis = new.analysis;
+        }
+        self.aspect_correct = new.aspect_correct;
+        self.render = new.render;
+        self.mirror = new.mirror;
+    }
+
+    /// Terminal columns used by one board cell for every terminal row.
+    fn column_ratio(&self) -> u16 {
+        if self.aspect_correct {
+            2
+        } else {
+            1
+        }
+    }
 }
 
-pub fn run_tui_app() -> io::Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// Screens drawn over the game, kept across games.
+#[derive(Default)]
+struct Overlays {
+    diagnostics: Diagnostics,
+    randomizer_stats: RandomizerScreen,
+}
 
-    terminal.clear()?;
-    terminal.hide_cursor()?;
+/// Everything taken from the config, swapped out when the file is edited.
+struct Settings {
+    config: Config,
+    controls: Controls,
+    style: BoardStyle,
+    watcher: Option<ConfigWatcher>,
+}
+
+impl Settings {
+    /// Applies any changes made to the config file. Controls and style
+    /// change right away, board settings with the next game. Returns whether
+    /// anything was reloaded.
+    fn reload(&mut self) -> bool {
+        let config = match self.watcher.as_mut().and_then(ConfigWatcher::poll) {
+            Some(Ok(config)) => config,
+            Some(Err(err)) => {
+                error!("Keeping the previous config: {}", err);
+                return false;
+            }
+            None => return false,
+        };
+        let controls = match config.controls() {
+            Ok(controls) => controls,
+            Err(err) => {
+                error!("Keeping the previous config: {}", err);
+                return false;
+            }
+        };
+
+        self.style.update(&self.config, &config);
+        self.controls = controls;
+        i18n::set_locale(config.language);
+        notify::set_enabled(config.notifications);
+        self.config = config;
+        info!("{}", i18n::messages().config_reloaded);
+        true
+    }
+}
+
+pub fn run_tui_app(config: &Config, args: &Args) -> Result<()> {
+    let mut external = match &args.bot {
+        Some(command) => Some(ExternalBot::spawn(command)?),
+        None => None,
+    };
+    i18n::set_locale(config.language);
+    notify::set_enabled(config.notifications);
+    if config.notifications && !cfg!(feature = "notifications") {
+        warn!("Notifications are on, but this build has no notifications feature");
+    }
+    terminal::install_panic_hook();
+    let mut terminal = TerminalGuard::new()?;
+    let mut overlays = Overlays::default();
+
+    let name = match args.player.clone().or_else(|| config.player.clone()) {
+        Some(name) => Some(name),
+        None => ask_player(&mut terminal, &player::known_players())?,
+    };
+    let mut player = name.map(|name| Player::load(&name));
+    save::set_player(player.as_ref().map(|player| player.name.as_str()));
+    let (config, profile) = player_config(config, args, player.as_mut());
+    if let Some(player) = &player {
+        if let Err(err) = player.save() {
+            error!("Cannot save the records of {}: {}", player.name, err);
+        }
+        let messages = i18n::messages();
+        info!(
+            "{}",
+            fill(messages.playing_as, &[&player.name, &player.bests.score])
+        );
+    }
+
+    let mut settings = Settings {
+        controls: config.controls()?,
+        style: BoardStyle::new(&config),
+        config,
+        watcher: ConfigWatcher::new(profile),
+    };
+
+    if args.tutorial {
+        return run_tutorial(&mut terminal, &mut settings);
+    }
+
+    // Single player games are autosaved, so offer to pick up the last one
+    let mut resumed = match (args.cpu, args.royale) {
+        (None, None) => save::load::<TetrisBoard>(),
+        _ => None,
+    };
+    if resumed.is_some() && !ask_resume(&mut terminal)? {
+        save::clear();
+        resumed = None;
+    }
 
     loop {
-        let mut tetris = TetrisBoard::new_default();
-        let state = run_game_loop(&mut terminal, &mut tetris)?;
-        if state == GameState::Quit {
-            break;
+        settings.reload();
+        let config = &settings.config;
+        let new_board = || {
+            TetrisBoard::new_default()
+                .with_spawn_delay(config.spawn_delay)
+                .with_spawn_rule(config.spawn_rule())
+                .with_goal(config.goal)
+                .with_randomizer(config.randomizer)
+                .with_piece_set(config.piece_set.clone())
+        };
+        let mut tetris = resumed.take().unwrap_or_else(new_board);
+        let mut opponents = match (args.cpu, args.royale) {
+            (Some(difficulty), _) => Opponents {
+                boards: vec![Opponent::new(new_board(), difficulty)],
+                royale: None,
+            },
+            // The bots play at a mix of difficulties
+            (None, Some(bots)) => Opponents {
+                boards: (0..bots)
+                    .map(|_| {
+                        let difficulty = Difficulty::ALL.choose(&mut rand::thread_rng());
+                        Opponent::new(new_board(), *difficulty.expect("not empty"))
+                    })
+                    .collect(),
+                royale: Some(Royale::new(bots, config.targeting)),
+            },
+            (None, None) => Opponents::default(),
+        };
+        let standard_pieces = tetris.view().pieces.is_standard();
+        let mut objectives = config.objectives.then(|| Objectives::new(standard_pieces));
+        let started = Instant::now();
+
+        let state = run_game_loop(
+            &mut terminal,
+            &mut tetris,
+            &mut opponents,
+            objectives.as_mut(),
+            external.as_mut(),
+            &mut settings,
+            &mut overlays,
+        )?;
+        // Only games the player played themselves count for their records
+        match (&mut player, &state) {
+            (_, GameState::Quit) | (None, _) => {}
+            (Some(_), _) if args.bot.is_some() => {}
+            (Some(player), _) => record_game(player, &tetris.view().stats, started.elapsed()),
+        }
+        let messages = i18n::messages();
+        match state {
+            GameState::Quit => break,
+            GameState::Failed if opponents.royale.is_some() => {
+                let boards = opponents.boards.len() + 1;
+                let place = 1 + opponents.boards.iter().filter(|o| o.board.alive()).count();
+                info!("{}", fill(messages.royale_out, &[&place, &boards]));
+            }
+            GameState::Failed => info!("{}", messages.game_over),
+            GameState::Won if opponents.royale.is_some() => info!("{}", messages.royale_won),
+            GameState::Won => info!("{}", messages.cpu_beaten),
         }
 
         if let Event::Key(key) = event::read()? {
@@ -57,56 +354,700 @@ pub fn run_tui_app() -> io::Result<()> {
         }
     }
 
-    // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-
-    terminal.show_cursor()?;
     Ok(())
 }
 
-fn run_game_loop<B: Backend, T: Tetris>(
+/// The config with the controls profile for `player` applied, and the
+/// profile to keep applying when the config is reloaded. A profile picked on
+/// the command line is remembered for the player, who otherwise gets the one
+/// they picked last.
+fn player_config(
+    config: &Config,
+    args: &Args,
+    player: Option<&mut Player>,
+) -> (Config, Option<String>) {
+    let mut config = config.clone();
+    let player = match player {
+        Some(player) => player,
+        None => return (config, args.profile.clone()),
+    };
+    if args.profile.is_some() {
+        player.profile = args.profile.clone();
+        return (config, args.profile.clone());
+    }
+
+    let saved = match &player.profile {
+        Some(saved) => saved.clone(),
+        None => return (config, None),
+    };
+    let default_profile = config.profile.replace(saved.clone());
+    if config.controls().is_ok() {
+        return (config, Some(saved));
+    }
+    error!(
+        "Ignoring the saved profile of {}, it no longer loads",
+        player.name
+    );
+    player.profile = None;
+    config.profile = default_profile;
+    (config, None)
+}
+
+/// Adds a finished game to the player's records and saves them.
+fn record_game(player: &mut Player, stats: &Stats, played: Duration) {
+    let messages = i18n::messages();
+    let best = player.bests.score;
+    if let Some(place) = player.record_game(stats, played) {
+        info!(
+            "{}",
+            fill(messages.new_high_score, &[&(place + 1), &player.name])
+        );
+    }
+    // A first game is a best by default, which is not worth interrupting for
+    if best > 0 && stats.score > best {
+        notify::send(
+            messages.title,
+            &fill(messages.new_personal_best, &[&stats.score]),
+        );
+    }
+    let session = &player.session;
+    info!(
+        "{}",
+        fill(
+            messages.session_stats,
+            &[&session.games, &session.lines, &(session.seconds / 60)]
+        )
+    );
+    if let Err(err) = player.save() {
+        error!("Cannot save the records of {}: {}", player.name, err);
+    }
+}
+
+/// Asks who is playing, a known player or a new one, returning `None` for a
+/// guest.
+fn ask_player<B: Backend>(terminal: &mut Terminal<B>, known: &[String]) -> Result<Option<String>> {
+    let messages = i18n::messages();
+    let players = if known.is_empty() {
+        messages.no_players.to_string()
+    } else {
+        fill(messages.known_players, &[&known.join(", ")])
+    };
+    let mut name = String::new();
+    loop {
+        terminal.draw(|f| {
+            let size = f.size();
+            let lines = vec![
+                Spans::from(messages.who_is_playing),
+                Spans::from(players.as_str()),
+                Spans::from(format!("> {}_", name)),
+            ];
+            let prompt = Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            let area = Rect {
+                y: size.height.saturating_sub(5) / 2,
+                height: 5.min(size.height),
+                ..size
+            };
+            f.render_widget(prompt, area);
+        })?;
+
+        if let Event::Key(key) = event::read()? {
+            match key.code {
+                KeyCode::Char(c)
+                    if player::is_name_char(c)
+                        && Player::validate_name(&format!("{}{}", name, c)).is_ok() =>
+                {
+                    name.push(c)
+                }
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Enter if name.is_empty() => return Ok(None),
+                KeyCode::Enter => return Ok(Some(name)),
+                KeyCode::Esc => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Walks the player through the controls, one step at a time on scripted
+/// boards, until they pass every step or quit.
+fn run_tutorial<B: Backend>(terminal: &mut Terminal<B>, settings: &mut Settings) -> Result<()> {
+    let mut tutorial = Tutorial::default();
+    let mut banner: Option<Banner> = None;
+    let mut title = WindowTitle::default();
+    let mut last_tick = Instant::now();
+    let mut tetris = match tutorial.step() {
+        Some(step) => step.board(),
+        None => return Ok(()),
+    };
+
+    while let Some(step) = tutorial.step() {
+        settings.reload();
+        let prompt = step.prompt(&settings.controls.bindings, settings.style.mirror);
+        let (number, steps) = tutorial.position();
+        let heading = fill(i18n::messages().tutorial, &[&number, &steps]);
+        terminal.draw(|f| {
+            draw_game(
+                f,
+                &mut tetris,
+                &Opponents::default(),
+                None,
+                &settings.style,
+                banner.as_ref(),
+            );
+            draw_tutorial_prompt(f, &heading, &prompt);
+        })?;
+        let messages = i18n::messages();
+        let score = fill(messages.window_score, &[&tetris.view().stats.score]);
+        title.set(fill(messages.window_title, &[&heading, &score]));
+
+        let mut progress = Progress::Going;
+        let timeout = TUTORIAL_GRAVITY
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0));
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                let action = settings.controls.bindings.action(key.code);
+                let moved = match action {
+                    Some(Action::Quit) => return Ok(()),
+                    Some(Action::MoveLeft) => {
+                        tetris.shift(Shift::Left);
+                        true
+                    }
+                    Some(Action::MoveRight) => {
+                        tetris.shift(Shift::Right);
+                        true
+                    }
+                    Some(Action::Rotate) => {
+                        tetris.rotate();
+                        true
+                    }
+                    Some(Action::Rotate180) => {
+                        tetris.rotate_180();
+                        true
+                    }
+                    Some(Action::SoftDrop) => tetris.soft_drop(),
+                    Some(Action::HardDrop) => tetris.hard_drop(),
+                    Some(Action::Hold) => tetris.hold(),
+                    _ => false,
+                };
+                if let Some(action) = action {
+                    progress = tutorial.handle_action(action, moved);
+                }
+            }
+        }
+
+        if last_tick.elapsed() >= TUTORIAL_GRAVITY {
+            tetris.tick();
+            last_tick = Instant::now();
+        }
+        for event in tetris.take_events() {
+            if progress == Progress::Going {
+                progress = tutorial.handle_event(&event);
+            }
+        }
+        if !tetris.alive() {
+            progress = Progress::Retry;
+        }
+        if matches!(&banner, Some(banner) if banner.until <= Instant::now()) {
+            banner = None;
+        }
+
+        match progress {
+            Progress::Going => continue,
+            Progress::Passed => tutorial.advance(),
+            Progress::Retry => {
+                banner = Some(Banner {
+                    text: i18n::messages().tutorial_retry,
+                    until: Instant::now() + BANNER_DURATION,
+                });
+            }
+        }
+        if let Some(step) = tutorial.step() {
+            tetris = step.board();
+            last_tick = Instant::now();
+        }
+    }
+
+    // Drawn again after anything but a key press, such as a resize
+    loop {
+        terminal.draw(|f| {
+            draw_game(
+                f,
+                &mut tetris,
+                &Opponents::default(),
+                None,
+                &settings.style,
+                None,
+            );
+            draw_tutorial_prompt(f, "", i18n::messages().tutorial_done);
+        })?;
+        if let Event::Key(_) = event::read()? {
+            return Ok(());
+        }
+    }
+}
+
+fn draw_tutorial_prompt<B: Backend>(f: &mut Frame<B>, title: &str, prompt: &str) {
+    let size = f.size();
+    let height = 4.min(size.height);
+    let area = Rect {
+        y: size.height - height,
+        height,
+        ..size
+    };
+    let paragraph = Paragraph::new(prompt)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().title(title).borders(Borders::ALL));
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// Draws a yes or no question with `draw`, redrawing it when the terminal is
+/// resized, and waits for the answer.
+fn ask_yes_no<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut draw: impl FnMut(&mut Frame<B>),
+) -> Result<bool> {
+    terminal.draw(&mut draw)?;
+
+    loop {
+        match event::read()? {
+            Event::Key(key) => match key.code {
+                KeyCode::Char(c) if c == i18n::messages().yes => return Ok(true),
+                KeyCode::Enter => return Ok(true),
+                KeyCode::Char(c) if c == i18n::messages().no => return Ok(false),
+                KeyCode::Esc => return Ok(false),
+                _ => {}
+            },
+            Event::Resize(..) => {
+                terminal.draw(&mut draw)?;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Asks over the game drawn by `draw` whether to really quit it, returning
+/// the answer.
+fn confirm_quit<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut draw: impl FnMut(&mut Frame<B>),
+) -> Result<bool> {
+    ask_yes_no(terminal, |f| {
+        draw(f);
+        let size = f.size();
+        let width = 30.min(size.width);
+        let area = Rect {
+            x: size.width.saturating_sub(width) / 2,
+            y: size.height.saturating_sub(3) / 2,
+            width,
+            height: 3.min(size.height),
+        };
+        let prompt = Paragraph::new(i18n::messages().quit_prompt)
+            .style(Style::default().fg(Color::Yellow))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(Clear, area);
+        f.render_widget(prompt, area);
+    })
+}
+
+/// Asks whether to resume the autosaved game, returning the answer.
+fn ask_resume<B: Backend>(terminal: &mut Terminal<B>) -> Result<bool> {
+    ask_yes_no(terminal, |f| {
+        let size = f.size();
+        let prompt = Paragraph::new(i18n::messages().resume_prompt)
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        let area = Rect {
+            y: size.height.saturating_sub(3) / 2,
+            height: 3.min(size.height),
+            ..size
+        };
+        f.render_widget(prompt, area);
+    })
+}
+
+fn run_game_loop<B: Backend, T: Tetris + Clone + Serialize>(
     terminal: &mut Terminal<B>,
     tetris: &mut T,
-) -> io::Result<GameState> {
-    let tick_rate = Duration::from_millis(500);
+    opponents: &mut Opponents,
+    mut objectives: Option<&mut Objectives>,
+    mut external: Option<&mut ExternalBot>,
+    settings: &mut Settings,
+    overlays: &mut Overlays,
+) -> Result<GameState> {
+    let mut tick_rate = level::gravity(tetris.view().stats.level);
+    let mut soft_drop_rate = settings.controls.soft_drop.interval(tick_rate);
     let mut last_tick = Instant::now();
+    let mut last_autosave = Instant::now();
+    // Games against the CPU are not saved, only the player's board would be
+    let autosave = opponents.boards.is_empty();
+    // Mistakes can only be taken back in casual play, with nobody to race
+    let mut rewind = (opponents.boards.is_empty() && external.is_none())
+        .then(Rewind::<(T, Option<Objectives>)>::default);
+    let mut last_soft_drop = Instant::now();
+    let mut soft_drop_until: Option<Instant> = None;
+    let mut banner: Option<Banner> = None;
+    // Whether anything on screen may have changed since the last frame
+    let mut dirty = true;
+    let mut title = WindowTitle::default();
+    let messages = i18n::messages();
+    let mode = if opponents.royale.is_some() {
+        messages.mode_royale
+    } else if !opponents.boards.is_empty() {
+        messages.mode_cpu
+    } else if external.is_some() {
+        messages.mode_bot
+    } else {
+        messages.mode_single
+    };
 
-    info!("Game started!");
+    info!("{}", i18n::messages().game_started);
     loop {
-        terminal.draw(|f| draw_game(f, tetris))?;
+        let frame_start = Instant::now();
+        if settings.reload() {
+            soft_drop_rate = settings.controls.soft_drop.interval(tick_rate);
+            if let Some(royale) = opponents.royale.as_mut() {
+                royale.set_targeting(settings.config.targeting);
+            }
+            dirty = true;
+        }
+
+        let style = &mut settings.style;
+        if dirty || style.render == RenderMode::Full {
+            terminal.draw(|f| {
+                draw_game(
+                    f,
+                    tetris,
+                    opponents,
+                    objectives.as_deref(),
+                    style,
+                    banner.as_ref(),
+                );
+                overlays.randomizer_stats.draw(f);
+                overlays.diagnostics.draw(f);
+            })?;
+            overlays.diagnostics.record_render(frame_start.elapsed());
+            dirty = false;
 
-        let timeout = tick_rate
+            let messages = i18n::messages();
+            let score = fill(messages.window_score, &[&tetris.view().stats.score]);
+            title.set(fill(messages.window_title, &[&mode, &score]));
+        }
+
+        let mut timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
+        if external.is_some() {
+            timeout = timeout.min(EXTERNAL_BOT_POLL);
+        }
+        for opponent in opponents.boards.iter().filter(|o| o.board.alive()) {
+            let bot_timeout = opponent
+                .bot
+                .difficulty()
+                .reaction_delay()
+                .checked_sub(opponent.last_move.elapsed())
+                .unwrap_or_else(|| Duration::from_secs(0));
+            timeout = timeout.min(bot_timeout);
+        }
+        if let (Some(_), Some(rate)) = (soft_drop_until, soft_drop_rate) {
+            let soft_drop_timeout = rate
+                .checked_sub(last_soft_drop.elapsed())
+                .unwrap_or_else(|| Duration::from_secs(0));
+            timeout = timeout.min(soft_drop_timeout);
+        }
 
-        if crossterm::event::poll(timeout)? {
+        let mut pending_events = 0;
+        let mut ready = crossterm::event::poll(timeout)?;
+        while ready {
+            pending_events += 1;
             if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => return Ok(GameState::Quit),
-                    KeyCode::Up => tetris.rotate(),
-                    KeyCode::Left => tetris.shift(crate::game::tetris::Direction::Left),
-                    KeyCode::Right => tetris.shift(crate::game::tetris::Direction::Right),
-                    _ => {}
+                match settings.controls.bindings.action(key.code) {
+                    Some(Action::Quit) => {
+                        let draw = |f: &mut Frame<B>| {
+                            draw_game(
+                                f,
+                                tetris,
+                                opponents,
+                                objectives.as_deref(),
+                                style,
+                                banner.as_ref(),
+                            )
+                        };
+                        let messages = i18n::messages();
+                        title.set(fill(messages.window_title, &[&mode, &messages.paused]));
+                        let paused = Instant::now();
+                        if !confirm_quit(terminal, draw)? {
+                            // Nothing moved while the question was up
+                            if let Some(rewind) = rewind.as_mut() {
+                                rewind.pause(paused.elapsed());
+                            }
+                            last_tick = Instant::now();
+                            for opponent in opponents.boards.iter_mut() {
+                                opponent.last_move = Instant::now();
+                            }
+                            soft_drop_until = None;
+                            ready = crossterm::event::poll(Duration::from_secs(0))?;
+                            continue;
+                        }
+                        // The autosave is for games cut short, not given up
+                        if autosave {
+                            save::clear();
+                        }
+                        return Ok(GameState::Quit);
+                    }
+                    Some(Action::ToggleDebug) => {
+                        overlays.diagnostics.visible = !overlays.diagnostics.visible
+                    }
+                    Some(Action::Rotate) => tetris.rotate(),
+                    Some(Action::Rotate180) => tetris.rotate_180(),
+                    Some(Action::SoftDrop) => {
+                        if soft_drop_until.is_none() {
+                            last_soft_drop = Instant::now();
+                            match soft_drop_rate {
+                                Some(_) => {
+                                    tetris.soft_drop();
+                                }
+                                None => while tetris.soft_drop() {},
+                            }
+                        }
+                        soft_drop_until = Some(Instant::now() + SOFT_DROP_HOLD);
+                    }
+                    Some(Action::HardDrop) => {
+                        tetris.hard_drop();
+                        // Key repeats from soft dropping should not carry
+                        // over to the next piece
+                        soft_drop_until = None;
+                    }
+                    Some(Action::Hold) => {
+                        tetris.hold();
+                    }
+                    Some(Action::MoveLeft) => tetris.shift(Shift::Left),
+                    Some(Action::MoveRight) => tetris.shift(Shift::Right),
+                    Some(Action::ZoomIn) => style.zoom = style.zoom.zoom_in(),
+                    Some(Action::ZoomOut) => style.zoom = style.zoom.zoom_out(),
+                    Some(Action::ToggleAnalysis) => style.analysis = !style.analysis,
+                    Some(Action::Rewind) => {
+                        let messages = i18n::messages();
+                        let affordable = match rewind.as_ref().and_then(Rewind::oldest) {
+                            Some((game, _)) => game.view().stats.score >= REWIND_COST,
+                            None => false,
+                        };
+                        if !affordable {
+                            info!("{}", fill(messages.rewind_refused, &[&REWIND_COST]));
+                        } else if let Some(((game, saved), back)) = rewind
+                            .as_mut()
+                            .and_then(|rewind| rewind.rewind(Instant::now()))
+                        {
+                            *tetris = game;
+                            tetris.take_events();
+                            tetris.deduct(REWIND_COST);
+                            if let (Some(objectives), Some(saved)) =
+                                (objectives.as_deref_mut(), saved)
+                            {
+                                *objectives = saved;
+                            }
+                            tick_rate = level::gravity(tetris.view().stats.level);
+                            soft_drop_rate = settings.controls.soft_drop.interval(tick_rate);
+                            soft_drop_until = None;
+                            let seconds = format!("{:.1}", back.as_secs_f64());
+                            info!("{}", fill(messages.rewound, &[&seconds, &REWIND_COST]));
+                        }
+                    }
+                    Some(Action::ToggleRandomizerStats) => {
+                        let pieces = tetris.view().pieces;
+                        overlays
+                            .randomizer_stats
+                            .toggle(settings.config.randomizer, &pieces)
+                    }
+                    None => {}
+                }
+            }
+            ready = crossterm::event::poll(Duration::from_secs(0))?;
+        }
+        overlays.diagnostics.record_events(pending_events);
+        dirty |= pending_events > 0;
+
+        if matches!(soft_drop_until, Some(until) if until <= Instant::now()) {
+            soft_drop_until = None;
+        }
+        if soft_drop_until.is_some() {
+            match soft_drop_rate {
+                Some(rate) if last_soft_drop.elapsed() >= rate => {
+                    dirty |= tetris.soft_drop();
+                    last_soft_drop = Instant::now();
+                }
+                Some(_) => {}
+                None => {
+                    while tetris.soft_drop() {
+                        dirty = true;
+                    }
+                }
+            }
+        }
+
+        if let Some(bot) = external.as_mut() {
+            if let Err(err) = bot.send(tetris.view()) {
+                error!("Stopped talking to the bot: {}", err);
+                external = None;
+            } else {
+                while let Some(action) = bot.next_action() {
+                    match action {
+                        Action::MoveLeft => tetris.shift(Shift::Left),
+                        Action::MoveRight => tetris.shift(Shift::Right),
+                        Action::Rotate => tetris.rotate(),
+                        Action::Rotate180 => tetris.rotate_180(),
+                        Action::SoftDrop => {
+                            tetris.soft_drop();
+                        }
+                        Action::HardDrop => {
+                            tetris.hard_drop();
+                        }
+                        Action::Hold => {
+                            tetris.hold();
+                        }
+                        _ => {}
+                    }
+                    dirty = true;
                 }
             }
         }
+
+        for opponent in opponents.boards.iter_mut() {
+            if opponent.board.alive()
+                && opponent.last_move.elapsed() >= opponent.bot.difficulty().reaction_delay()
+            {
+                opponent.play_move();
+                dirty = true;
+            }
+        }
+
         if last_tick.elapsed() >= tick_rate {
+            overlays
+                .diagnostics
+                .record_tick_drift(last_tick.elapsed() - tick_rate);
             tetris.tick();
+            for opponent in opponents.boards.iter_mut() {
+                opponent.board.tick();
+            }
             last_tick = Instant::now();
+            if autosave {
+                save::record(tetris);
+            }
+            if let Some(rewind) = rewind.as_mut() {
+                rewind.record((tetris.clone(), objectives.as_deref().cloned()), last_tick);
+            }
+            dirty = true;
+
+            // The CPU boards fall in step with the player's, at their level
+            tick_rate = level::gravity(tetris.view().stats.level);
+            soft_drop_rate = settings.controls.soft_drop.interval(tick_rate);
+        }
+
+        let messages = i18n::messages();
+        // Garbage sent by each board, the player's being board 0
+        let mut attacks = vec![];
+        for event in tetris.take_events() {
+            if let GameEvent::Attack { lines } = event {
+                attacks.push((0, lines));
+            }
+            if let GameEvent::AllClear { .. } = event {
+                info!("{}", messages.all_clear);
+                banner = Some(Banner {
+                    text: messages.all_clear_banner,
+                    until: Instant::now() + BANNER_DURATION,
+                });
+            }
+
+            if let Some(objectives) = objectives.as_mut() {
+                let had_objective = objectives.current().is_some();
+                if let Some(objective) = objectives.handle(&event) {
+                    let description = messages.objective(objective);
+                    info!("{}", fill(messages.objective_complete, &[&description]));
+                    tetris.award(objective.bonus());
+                    banner = Some(Banner {
+                        text: messages.objective_complete_banner,
+                        until: Instant::now() + BANNER_DURATION,
+                    });
+                } else if let (false, Some(objective)) = (had_objective, objectives.current()) {
+                    let description = messages.objective(objective);
+                    info!("{}", fill(messages.new_objective, &[&description]));
+                    banner = Some(Banner {
+                        text: messages.new_objective_banner,
+                        until: Instant::now() + BANNER_DURATION,
+                    });
+                }
+            }
+        }
+        for (index, opponent) in opponents.boards.iter_mut().enumerate() {
+            for event in opponent.board.take_events() {
+                if let GameEvent::Attack { lines } = event {
+                    attacks.push((index + 1, lines));
+                }
+            }
+        }
+        if let Some(royale) = opponents.royale.as_mut() {
+            if !attacks.is_empty() {
+                let standings = standings(tetris, &opponents.boards);
+                for (from, lines) in attacks {
+                    match royale.attack(from, &standings) {
+                        Some(0) => tetris.receive_garbage(lines),
+                        Some(target) => opponents.boards[target - 1].board.receive_garbage(lines),
+                        None => {}
+                    }
+                }
+                dirty = true;
+            }
+            for index in 0..opponents.boards.len() {
+                let opponent = &mut opponents.boards[index];
+                if !opponent.out && !opponent.board.alive() {
+                    opponent.out = true;
+                    let left = Royale::remaining(&standings(tetris, &opponents.boards));
+                    info!("{}", fill(messages.bot_out, &[&(index + 1), &left]));
+                }
+            }
+        }
+        if matches!(&banner, Some(banner) if banner.until <= Instant::now()) {
+            banner = None;
+            dirty = true;
+        }
+
+        if autosave && last_autosave.elapsed() >= AUTOSAVE_INTERVAL {
+            if let Err(err) = save::flush() {
+                error!("Cannot autosave the game: {}", err);
+            }
+            last_autosave = Instant::now();
         }
 
         if !tetris.alive() {
+            if autosave {
+                save::clear();
+            }
             return Ok(GameState::Failed);
         }
+        if opponents.all_out() {
+            return Ok(GameState::Won);
+        }
+
+        overlays.diagnostics.record_frame(frame_start.elapsed());
     }
 }
 
-fn draw_game<B: Backend, T: Tetris>(f: &mut Frame<B>, tetris: &mut T) {
+fn draw_game<B: Backend, T: Tetris>(
+    f: &mut Frame<B>,
+    tetris: &mut T,
+    opponents: &Opponents,
+    objectives: Option<&Objectives>,
+    style: &BoardStyle,
+    banner: Option<&Banner>,
+) {
     let size = f.size();
 
     let chunks = Layout::default()
@@ -117,11 +1058,11 @@ fn draw_game<B: Backend, T: Tetris>(f: &mut Frame<B>, tetris: &mut T) {
     let title = draw_title();
     f.render_widget(title, chunks[0]);
 
-    draw_game_board(f, tetris, chunks[1]);
+    draw_game_board(f, tetris, opponents, objectives, style, banner, chunks[1]);
 }
 
 fn draw_title<'a>() -> Paragraph<'a> {
-    Paragraph::new("Tetris TUI")
+    Paragraph::new(i18n::messages().title)
         .style(Style::default().fg(Color::LightCyan))
         .alignment(Alignment::Center)
         .block(
@@ -132,21 +1073,20 @@ fn draw_title<'a>() -> Paragraph<'a> {
         )
 }
 
-impl From<Shape> for Color {
-    fn from(shape: Shape) -> Color {
-        match shape {
-            Shape::I => Color::Rgb(0, 255, 255),
-            Shape::O => Color::Rgb(255, 255, 0),
-            Shape::T => Color::Rgb(128, 0, 128),
-            Shape::J => Color::Rgb(0, 255, 0),
-            Shape::L => Color::Rgb(255, 0, 0),
-            Shape::S => Color::Rgb(0, 0, 255),
-            Shape::Z => Color::Rgb(255, 127, 0),
-        }
-    }
+fn shape_color(view: &BoardView, shape: Shape) -> Color {
+    let (red, green, blue) = view.pieces.color(shape);
+    Color::Rgb(red, green, blue)
 }
 
-fn draw_game_board<B: Backend, T: Tetris>(f: &mut Frame<B>, tetris: &mut T, area: Rect) {
+fn draw_game_board<B: Backend, T: Tetris>(
+    f: &mut Frame<B>,
+    tetris: &mut T,
+    opponents: &Opponents,
+    objectives: Option<&Objectives>,
+    style: &BoardStyle,
+    banner: Option<&Banner>,
+    area: Rect,
+) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
@@ -161,43 +1101,157 @@ fn draw_game_board<B: Backend, T: Tetris>(f: &mut Frame<B>, tetris: &mut T, area
         .split(area);
 
     let block = Block::default().borders(Borders::ALL).title(Span::styled(
-        "Game Board",
+        i18n::messages().board,
         Style::default().fg(Color::Rgb(170, 143, 121)),
     ));
 
     f.render_widget(block, area);
 
-    // Logs
-    let logs = draw_logs();
-    f.render_widget(logs, chunks[0]);
+    let view = tetris.view();
+    // Bots that do not fit on the left go under the side panel
+    let mut thumbnails_right = None;
 
-    let (width, height) = tetris.board_size();
+    match (&opponents.royale, opponents.boards.first()) {
+        (Some(royale), _) => {
+            let thumbnails = thumbnails(opponents, royale);
+            let left_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(ROYALE_LOG_HEIGHT)].as_ref())
+                .split(chunks[0]);
+            let drawn = thumbnail::draw_thumbnails(f, &thumbnails, style.mirror, left_chunks[0]);
+            f.render_widget(draw_logs(), left_chunks[1]);
+            thumbnails_right = Some((thumbnails, drawn));
+        }
+        (None, Some(opponent)) => {
+            let messages = i18n::messages();
+            let difficulty = messages.difficulty(opponent.bot.difficulty());
+            let title = fill(messages.cpu, &[&difficulty]);
+            let cpu_block = Block::default().title(title).borders(Borders::ALL);
+            let cpu_area = cpu_block.inner(chunks[0]);
+            f.render_widget(cpu_block, chunks[0]);
+            draw_board(f, &opponent.board.view(), style, cpu_area);
+        }
+        (None, None) => {
+            // Logs
+            let logs = draw_logs();
+            f.render_widget(logs, chunks[0]);
+        }
+    }
 
-    let area_len = std::cmp::min(chunks[1].width, chunks[1].height) as i32;
+    let mut side_lines = vec![];
+    let mut side_constraints = vec![Constraint::Min(0)];
+    if let Some(royale) = &opponents.royale {
+        side_lines = royale_lines(tetris, opponents, royale, &view);
+        side_constraints = vec![Constraint::Length(ROYALE_PANEL_HEIGHT)];
+    }
+    if objectives.is_some() {
+        side_constraints.push(Constraint::Length(5));
+    }
+    if thumbnails_right.is_some() {
+        side_constraints.push(Constraint::Min(0));
+    }
+    let side_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(side_constraints)
+        .split(chunks[2]);
+    let mut side_chunks = side_chunks.into_iter();
+    if let Some(area) = side_chunks.next() {
+        f.render_widget(draw_side_panel(&view, side_lines), area);
+    }
+    if let Some(objectives) = objectives {
+        if let Some(area) = side_chunks.next() {
+            f.render_widget(draw_objectives(objectives), area);
+        }
+    }
+    if let Some((thumbnails, drawn)) = thumbnails_right {
+        if let Some(area) = side_chunks.next() {
+            thumbnail::draw_thumbnails(f, &thumbnails[drawn..], style.mirror, area);
+        }
+    }
 
-    if area_len < width || area_len < height {
-        error!("The screen is too small to show game board");
-        return;
+    if style.analysis {
+        let board_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(2)].as_ref())
+            .split(chunks[1]);
+        if let Some(board_area) = draw_board(f, &view, style, board_chunks[0]) {
+            draw_analysis(f, &view, style, board_area, board_chunks[1]);
+        }
+    } else {
+        draw_board(f, &view, style, chunks[1]);
+    }
+
+    if let Some(banner) = banner {
+        draw_banner(f, banner, chunks[1]);
     }
+}
+
+fn draw_banner<B: Backend>(f: &mut Frame<B>, banner: &Banner, area: Rect) {
+    let width = (banner.text.chars().count() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+    let banner_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let paragraph = Paragraph::new(banner.text)
+        .style(Style::default().fg(Color::Yellow).bg(Color::Black))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
 
-    let block_ratio = std::cmp::min(area_len / width, area_len / height);
+    f.render_widget(Clear, banner_area);
+    f.render_widget(paragraph, banner_area);
+}
+
+/// Draws the board centred at the top of `area`, returning where it went.
+fn draw_board<B: Backend>(
+    f: &mut Frame<B>,
+    view: &BoardView,
+    style: &BoardStyle,
+    area: Rect,
+) -> Option<Rect> {
+    let (width, height) = (view.width, view.height);
+
+    let column_ratio = style.column_ratio();
+    let fit_rows = std::cmp::min(
+        area.height / height as u16,
+        area.width / (width as u16 * column_ratio),
+    );
+    let cell_rows = std::cmp::min(style.zoom.cell_rows(), fit_rows);
+
+    if cell_rows == 0 {
+        error!("{}", i18n::messages().screen_too_small);
+        return None;
+    }
 
-    let block_width = block_ratio * width;
-    let block_height = block_ratio * height;
+    let block_width = cell_rows * column_ratio * width as u16;
+    let block_height = cell_rows * height as u16;
 
     let block_area = Rect {
-        x: chunks[1].x + 2,
-        y: chunks[1].y,
-        width: block_width as u16,
-        height: block_height as u16,
+        x: area.x + (area.width - block_width) / 2,
+        y: area.y,
+        width: block_width,
+        height: block_height,
+    };
+
+    // Borders need at least two rows and columns to draw anything but corners
+    let cell_borders = if cell_rows > 1 && style.render == RenderMode::Full {
+        Borders::ALL
+    } else {
+        Borders::NONE
     };
 
     let board_cells = split_rect_into_tetris_squre(block_area, width, height);
 
     for (index, cell) in board_cells.into_iter().enumerate() {
         let (x, y) = convert_index_to_cords(index as i32, width);
-        let color: Color = if let Some(shape) = tetris.get((x, y).into()) {
-            shape.into()
+        let x = if style.mirror { width - 1 - x } else { x };
+        let color = if let Some(shape) = view.get((x, y).into()) {
+            shape_color(view, shape)
+        } else if view.is_ghost((x, y).into()) {
+            Color::Rgb(90, 90, 90)
         } else {
             Color::Rgb(127, 127, 127)
         };
@@ -205,10 +1259,166 @@ fn draw_game_board<B: Backend, T: Tetris>(f: &mut Frame<B>, tetris: &mut T, area
         let block = Block::default()
             .style(Style::default().bg(color))
             .border_type(BorderType::Plain)
-            .borders(Borders::ALL);
+            .borders(cell_borders);
 
         f.render_widget(block, cell);
     }
+
+    Some(block_area)
+}
+
+/// Writes each column's height and, below it, its hole count under the
+/// columns of the board drawn in `board_area`.
+fn draw_analysis<B: Backend>(
+    f: &mut Frame<B>,
+    view: &BoardView,
+    style: &BoardStyle,
+    board_area: Rect,
+    area: Rect,
+) {
+    let area = Rect {
+        x: board_area.x,
+        width: board_area.width,
+        ..area
+    };
+    let mut columns = split_rect_by_direction(area, view.width, Direction::Horizontal);
+    if style.mirror {
+        columns.reverse();
+    }
+
+    for (stats, column) in view.columns.iter().zip(columns) {
+        let holes_color = if stats.holes > 0 {
+            Color::Red
+        } else {
+            Color::DarkGray
+        };
+        let lines = vec![
+            Spans::from(stats.height.to_string()),
+            Spans::from(Span::styled(
+                stats.holes.to_string(),
+                Style::default().fg(holes_color),
+            )),
+        ];
+        f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), column);
+    }
+}
+
+/// The bots of a battle royale drawn small, marking the one the player
+/// attacked last in yellow and the ones whose last attack hit the player in
+/// red.
+fn thumbnails(opponents: &Opponents, royale: &Royale) -> Vec<Thumbnail> {
+    opponents
+        .boards
+        .iter()
+        .enumerate()
+        .map(|(index, opponent)| {
+            let board = index + 1;
+            let border = if royale.last_target(0) == Some(board) {
+                Color::Yellow
+            } else if royale.last_target(board) == Some(0) {
+                Color::Red
+            } else {
+                Color::Reset
+            };
+            Thumbnail {
+                view: opponent.board.view(),
+                title: board.to_string(),
+                border,
+                out: !opponent.board.alive(),
+            }
+        })
+        .collect()
+}
+
+/// Side panel lines on how the player's battle royale is going.
+fn royale_lines<'a, T: Tetris>(
+    tetris: &T,
+    opponents: &Opponents,
+    royale: &Royale,
+    view: &BoardView,
+) -> Vec<Spans<'a>> {
+    let messages = i18n::messages();
+    let boards = opponents.boards.len() + 1;
+    let left = Royale::remaining(&standings(tetris, &opponents.boards));
+    let incoming_color = if view.garbage > 0 {
+        Color::Red
+    } else {
+        Color::Reset
+    };
+    vec![
+        Spans::default(),
+        Spans::from(fill(messages.boards_left, &[&left, &boards])),
+        Spans::from(fill(
+            messages.targeting,
+            &[&messages.targeting(royale.targeting())],
+        )),
+        Spans::from(Span::styled(
+            fill(messages.incoming, &[&view.garbage]),
+            Style::default().fg(incoming_color),
+        )),
+    ]
+}
+
+fn draw_side_panel<'a>(view: &BoardView, extra: Vec<Spans<'a>>) -> Paragraph<'a> {
+    let messages = i18n::messages();
+    let held = match view.held {
+        Some(shape) => Span::styled(
+            view.name(shape).to_string(),
+            Style::default().fg(shape_color(view, shape)),
+        ),
+        None => Span::raw("-"),
+    };
+    let mut lines = vec![
+        Spans::from(vec![Span::raw(messages.hold), held]),
+        Spans::from(messages.next),
+    ];
+    lines.extend(view.queue.iter().map(|&shape| {
+        Spans::from(Span::styled(
+            format!("  {}", view.name(shape)),
+            Style::default().fg(shape_color(view, shape)),
+        ))
+    }));
+    lines.push(Spans::default());
+    lines.push(Spans::from(fill(messages.level, &[&view.stats.level])));
+    lines.push(Spans::from(fill(
+        messages.goal,
+        &[&view.stats.goal_remaining],
+    )));
+    lines.push(Spans::from(fill(messages.score, &[&view.stats.score])));
+    lines.push(Spans::from(fill(messages.lines, &[&view.stats.lines])));
+    lines.push(Spans::from(fill(messages.pieces, &[&view.stats.pieces])));
+    lines.push(Spans::from(fill(
+        messages.all_clears,
+        &[&view.stats.all_clears],
+    )));
+    lines.extend(extra);
+
+    Paragraph::new(lines).block(Block::default().title(messages.stats).borders(Borders::ALL))
+}
+
+fn draw_objectives<'a>(objectives: &Objectives) -> Paragraph<'a> {
+    let messages = i18n::messages();
+    let current = match objectives.current() {
+        Some(objective) => Spans::from(Span::styled(
+            messages.objective(objective),
+            Style::default().fg(Color::Yellow),
+        )),
+        None => Spans::from(messages.no_objective),
+    };
+    let lines = vec![
+        current,
+        Spans::default(),
+        Spans::from(fill(
+            messages.objectives_completed,
+            &[&objectives.completed()],
+        )),
+    ];
+
+    Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .title(messages.objective)
+            .borders(Borders::ALL),
+    )
 }
 
 fn split_rect_into_tetris_squre(area: Rect, width: i32, height: i32) -> Vec<Rect> {
@@ -227,7 +1437,7 @@ fn split_rect_by_direction(area: Rect, counts: i32, dir: Direction) -> Vec<Rect>
 
     Layout::default()
         .direction(dir)
-        .constraints(constraints.as_ref())
+        .constraints(constraints)
         .margin(0)
         .split(area)
 }
@@ -245,7 +1455,11 @@ fn draw_logs<'a>() -> TuiLoggerWidget<'a> {
         .style_warn(Style::default().fg(Color::Yellow))
         .style_trace(Style::default().fg(Color::Gray))
         .style_info(Style::default().fg(Color::Blue))
-        .block(Block::default().title("Logs").borders(Borders::ALL))
+        .block(
+            Block::default()
+                .title(i18n::messages().logs)
+                .borders(Borders::ALL),
+        )
         .style(Style::default().fg(Color::White).bg(Color::Black))
 }
 
@@ -260,4 +1474,27 @@ mod tests {
         assert_eq!(convert_index_to_cords(11, 10), (1, 1));
         assert_eq!(convert_index_to_cords(25, 10), (5, 2));
     }
+
+    #[test]
+    fn test_style_update_keeps_toggles() {
+        let old = Config::default();
+        let mut style = BoardStyle::new(&old);
+        style.zoom = Zoom::Small;
+        style.analysis = true;
+
+        let new = Config {
+            aspect_correct: false,
+            ..Config::default()
+        };
+        style.update(&old, &new);
+        assert_eq!((style.zoom, style.analysis), (Zoom::Small, true));
+        assert!(!style.aspect_correct);
+
+        let newer = Config {
+            zoom: Zoom::Medium,
+            ..new.clone()
+        };
+        style.update(&new, &newer);
+        assert_eq!(style.zoom, Zoom::Medium);
+    }
 }
@@ -0,0 +1,238 @@
+use crate::game::event::GameEvent;
+use crate::game::shape::Shape;
+use crate::game::tetris::TetrisBoard;
+use crate::input::{Action, KeyBindings};
+
+use super::i18n::{self, fill};
+
+/// One lesson of the tutorial, passed by doing what it asks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Move,
+    Rotate,
+    Rotate180,
+    SoftDrop,
+    HardDrop,
+    Hold,
+    ClearLines,
+    TSpin,
+}
+
+impl Step {
+    const ALL: [Step; 8] = [
+        Step::Move,
+        Step::Rotate,
+        Step::Rotate180,
+        Step::SoftDrop,
+        Step::HardDrop,
+        Step::Hold,
+        Step::ClearLines,
+        Step::TSpin,
+    ];
+
+    /// The board the step starts from, and starts over from on a retry.
+    pub fn board(self) -> TetrisBoard {
+        let board = TetrisBoard::new_default();
+        match self {
+            Step::Move | Step::Rotate | Step::Rotate180 => board.with_pieces(&[Shape::T]),
+            Step::SoftDrop => board.with_pieces(&[Shape::L]),
+            Step::HardDrop => board.with_pieces(&[Shape::J]),
+            Step::Hold => board.with_pieces(&[Shape::S, Shape::I]),
+            Step::ClearLines => board
+                .with_stack(&["ZZSSOO.LLJ", "ZZSSOO.LLJ", "ZZSSOO.LLJ", "ZZSSOO.LLJ"])
+                .with_pieces(&[Shape::I]),
+            // A T-spin double slot with an overhang on its left
+            Step::TSpin => board
+                .with_stack(&["JJJ.......", "JL...SSZZO", "LLL.IIIIOO"])
+                .with_pieces(&[Shape::T]),
+        }
+    }
+
+    /// What the player is asked to do, naming the keys they play with on a
+    /// board drawn mirrored or not.
+    pub fn prompt(self, bindings: &KeyBindings, mirror: bool) -> String {
+        let messages = i18n::messages();
+        let keys = |action| bindings.keys_for(action);
+        match self {
+            // On a mirrored board the piece moves left on screen when it
+            // moves right on the board
+            Step::Move if mirror => fill(
+                messages.tutorial_move,
+                &[&keys(Action::MoveRight), &keys(Action::MoveLeft)],
+            ),
+            Step::Move => fill(
+                messages.tutorial_move,
+                &[&keys(Action::MoveLeft), &keys(Action::MoveRight)],
+            ),
+            Step::Rotate => fill(messages.tutorial_rotate, &[&keys(Action::Rotate)]),
+            Step::Rotate180 => fill(messages.tutorial_rotate_180, &[&keys(Action::Rotate180)]),
+            Step::SoftDrop => fill(messages.tutorial_soft_drop, &[&keys(Action::SoftDrop)]),
+            Step::HardDrop => fill(messages.tutorial_hard_drop, &[&keys(Action::HardDrop)]),
+            Step::Hold => fill(messages.tutorial_hold, &[&keys(Action::Hold)]),
+            Step::ClearLines => messages.tutorial_clear_lines.to_string(),
+            Step::TSpin => fill(
+                messages.tutorial_t_spin,
+                &[&keys(Action::Rotate), &keys(Action::SoftDrop)],
+            ),
+        }
+    }
+}
+
+/// What a move or event did for the step in progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    Going,
+    Passed,
+    /// The step can no longer be passed on this board.
+    Retry,
+}
+
+/// Walks through the steps in order, each only after the last was passed.
+#[derive(Debug, Default)]
+pub struct Tutorial {
+    step: usize,
+    moved_left: bool,
+    moved_right: bool,
+}
+
+impl Tutorial {
+    /// The step in progress, or `None` once the tutorial is done.
+    pub fn step(&self) -> Option<Step> {
+        Step::ALL.get(self.step).copied()
+    }
+
+    /// Number of the step in progress and of steps in all, for display.
+    pub fn position(&self) -> (usize, usize) {
+        (self.step + 1, Step::ALL.len())
+    }
+
+    /// Moves on to the next step.
+    pub fn advance(&mut self) {
+        *self = Self {
+            step: self.step + 1,
+            ..Self::default()
+        };
+    }
+
+    /// Handles an action the player took, `moved` being whether the piece
+    /// moved because of it, or for a hard drop or hold whether it did
+    /// anything.
+    pub fn handle_action(&mut self, action: Action, moved: bool) -> Progress {
+        let passed = match (self.step(), action) {
+            (Some(Step::Move), Action::MoveLeft) => {
+                self.moved_left = true;
+                self.moved_right
+            }
+            (Some(Step::Move), Action::MoveRight) => {
+                self.moved_right = true;
+                self.moved_left
+            }
+            (Some(Step::Rotate), Action::Rotate) | (Some(Step::Rotate180), Action::Rotate180) => {
+                true
+            }
+            // Soft dropping into the floor means the piece got all the way
+            (Some(Step::SoftDrop), Action::SoftDrop) => !moved,
+            // Passed by the piece locking from the drop, not from gravity
+            (Some(Step::HardDrop), Action::HardDrop) | (Some(Step::Hold), Action::Hold) => moved,
+            _ => false,
+        };
+        if passed {
+            Progress::Passed
+        } else {
+            Progress::Going
+        }
+    }
+
+    /// Handles an event from the board.
+    pub fn handle_event(&mut self, event: &GameEvent) -> Progress {
+        match (self.step(), event) {
+            (Some(Step::ClearLines), GameEvent::Locked { lines, .. }) if *lines > 0 => {
+                Progress::Passed
+            }
+            (
+                Some(Step::TSpin),
+                GameEvent::Locked {
+                    lines,
+                    t_spin: true,
+                    ..
+                },
+            ) if *lines > 0 => Progress::Passed,
+            (Some(Step::ClearLines | Step::TSpin), GameEvent::Locked { .. }) => Progress::Retry,
+            _ => Progress::Going,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tetris::{Direction, Tetris};
+
+    #[test]
+    fn test_hard_drop_and_hold_steps() {
+        let mut tutorial = Tutorial::default();
+        while tutorial.step() != Some(Step::HardDrop) {
+            tutorial.advance();
+        }
+
+        // A piece landing by gravity does not count
+        let mut board = Step::HardDrop.board();
+        while board.soft_drop() {}
+        board.tick();
+        assert_eq!(board.view().stats.pieces, 1);
+        assert_eq!(
+            tutorial.handle_action(Action::SoftDrop, false),
+            Progress::Going
+        );
+        let mut board = Step::HardDrop.board();
+        assert_eq!(
+            tutorial.handle_action(Action::HardDrop, board.hard_drop()),
+            Progress::Passed
+        );
+
+        tutorial.advance();
+        let mut board = Step::Hold.board();
+        assert_eq!(
+            tutorial.handle_action(Action::Hold, board.hold()),
+            Progress::Passed
+        );
+        // Holding again before the piece locks does nothing
+        assert_eq!(
+            tutorial.handle_action(Action::Hold, board.hold()),
+            Progress::Going
+        );
+    }
+
+    #[test]
+    fn test_t_spin_step() {
+        let mut tutorial = Tutorial::default();
+        while tutorial.step() != Some(Step::TSpin) {
+            tutorial.advance();
+        }
+
+        // Point the T right, drop it beside the slot and turn it in
+        let mut board = Step::TSpin.board();
+        board.rotate();
+        board.shift(Direction::Left);
+        while board.soft_drop() {}
+        assert_eq!(
+            tutorial.handle_action(Action::SoftDrop, false),
+            Progress::Going
+        );
+        board.rotate();
+        board.tick();
+
+        let events = board.take_events();
+        assert_eq!(
+            events[0],
+            GameEvent::Locked {
+                shape: Shape::T,
+                lines: 2,
+                t_spin: true
+            }
+        );
+        assert_eq!(tutorial.handle_event(&events[0]), Progress::Passed);
+        tutorial.advance();
+        assert_eq!(tutorial.step(), None);
+    }
+}